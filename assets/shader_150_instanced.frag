@@ -2,11 +2,10 @@
 
 in vec3 v_FragPos;
 in vec3 v_Normal;
+in vec4 v_Color;
 
 out vec4 o_Color;
 
-uniform vec4 t_color;
-
 
 void main() 
 {
@@ -35,6 +34,6 @@ void main()
     float spec = pow(max(dot(viewDir, reflectDir), 0.0), shininess);
     vec3 specular = specularStrength * spec * lightColor;
 
-    vec3 result = (ambient + diffuse + specular) * vec3(t_color);
+    vec3 result = (ambient + diffuse + specular) * vec3(v_Color);
     o_Color = vec4(result, 1.0);
 }
@@ -0,0 +1,40 @@
+#version 150 core
+
+in vec3 a_pos;
+in vec3 a_normal;
+
+in vec4 i_model_0;
+in vec4 i_model_1;
+in vec4 i_model_2;
+in vec4 i_model_3;
+in vec4 i_color;
+in float i_ring_growth;
+
+
+out vec3 v_FragPos;
+out vec3 v_Normal;
+out vec4 v_Color;
+
+uniform mat4 u_view_proj;
+
+void main() 
+{
+    mat4 model = mat4(i_model_0, i_model_1, i_model_2, i_model_3);
+
+    // Ring meshes are widened around their axis, the cross section and its normals stay the same.
+    vec3 pos = a_pos;
+    float distance = length(pos.xy);
+
+    if (distance > 0.0) {
+        pos.xy += pos.xy / distance * i_ring_growth;
+    }
+
+    vec4 world_pos = model * vec4(pos, 1.0);
+
+    gl_Position = u_view_proj * world_pos;
+
+    // The lighting happens in world space.
+    v_FragPos = vec3(world_pos);
+    v_Normal = mat3(transpose(inverse(model))) * a_normal;
+    v_Color = i_color;
+}
@@ -1,193 +1,315 @@
 #![allow(dead_code)]
-#![allow(nonstandard_style)]
+
+use std::cell::RefCell;
+use std::rc::Weak;
 
 use crate::{
-    math,
-    base_mesh_trait::MeshDesc
+    base_mesh_trait::{self, MeshDesc},
+    mesh_cache::MeshHandle,
+    mesh_error::MeshError,
+    quaternion::Quaternion,
+    renderer::Renderer,
+    transform::Transform,
 };
 
 
-pub struct ActorBase<PIPE_DATA, PIPE: gfx::pso::PipelineInit>
+/// What the renderer needs of an actor: the shared mesh, the colour and the placement.
+/// It's also a node of the scene graph, the placement is relative to the parent node.
+pub struct ActorBase
 {   
-    pub mesh_data: PIPE_DATA,
-    pub slice:     gfx::Slice<gfx_device_gl::Resources>,
-    pub pso:       gfx::PipelineState<gfx_device_gl::Resources, 
-                                      <PIPE as gfx::pso::PipelineInit>::Meta>,
-
-    not_worthy_of_update: bool,
+    // Group nodes have no mesh, they only place their children.
+    pub mesh:  Option<MeshHandle>,
+    pub color: [f32; 4],
+    // How far a ring mesh is widened around its axis, disks of every width share one mesh.
+    pub ring_growth: f32,
 
-    model: vecmath::Matrix4<f32>,
+    // The local or the parent's transform changed since the world matrix was computed.
+    dirty: bool,
 
-    positon: vecmath::Vector3<f32>,
+    local: vecmath::Matrix4<f32>,
+    world: vecmath::Matrix4<f32>,
 
-    scale: vecmath::Vector3<f32>,
+    pub(crate) parent: Option<Weak<RefCell<ActorBase>>>,
+    pub(crate) children: Vec<Weak<RefCell<ActorBase>>>,
 
-    rotation_x: f32,
-    rotation_y: f32,
-    rotation_z: f32,
-    rotation_mat_x: vecmath::Matrix4<f32>,
-    rotation_mat_y: vecmath::Matrix4<f32>,
-    rotation_mat_z: vecmath::Matrix4<f32>,
+    transform: Transform,
 }
 
 
 
-impl<PIPE_DATA, PIPE: gfx::pso::PipelineInit> ActorBase<PIPE_DATA, PIPE> 
+impl ActorBase 
 {
-    pub fn new(mesh_data: PIPE_DATA,
-               slice:     gfx::Slice<gfx_device_gl::Resources>,
-               pso:       gfx::PipelineState<gfx_device_gl::Resources, <PIPE as gfx::pso::PipelineInit>::Meta>) -> Self
+    // Every actor starts with its own random colour.
+    pub fn new(mesh: MeshHandle) -> Self
     {
         ActorBase {
-            mesh_data: mesh_data,
-            slice: slice,
-            pso: pso,
-            not_worthy_of_update: false,
-            model: (vecmath::mat4_id()),
-            positon: ([ 0., 0., 0. ]), 
-            scale: ([ 1., 1., 1. ]), 
-            rotation_x: (0.0),
-            rotation_y: (0.0),
-            rotation_z: (0.0),
-            rotation_mat_y: (vecmath::mat4_id()), 
-            rotation_mat_x: (vecmath::mat4_id()),
-            rotation_mat_z: (vecmath::mat4_id()),
+            mesh: Some(mesh),
+            ..ActorBase::group()
         }
     }
-}
 
 
 
-#[inline]
-fn add_angle_with_overflow(a: &mut f32, b: f32)
-{
-    *a += b;
-    if *a >= math::TWO_PI {
-        *a -= math::TWO_PI;
+    pub fn group() -> Self
+    {
+        ActorBase {
+            mesh: None,
+            color: base_mesh_trait::random_color(),
+            ring_growth: 0.,
+            dirty: true,
+            local: (vecmath::mat4_id()),
+            world: (vecmath::mat4_id()),
+            parent: None,
+            children: Vec::new(),
+            transform: Transform::identity(),
+        }
     }
-}
 
 
 
-pub trait Actor
-{
-    fn initialize(mesh_desc: MeshDesc,
-                  open_gl:   &crate::piston_window::OpenGL, 
-                  window:    &piston_window::PistonWindow,
-                  factory:   &mut gfx_device_gl::Factory) -> Self;
+    // The children only get dirty with their parent, a dirty node has dirty children already.
+    pub fn mark_dirty(&mut self)
+    {
+        if self.dirty {
+            return;
+        }
 
+        self.dirty = true;
+
+        for child in self.children.iter().filter_map(Weak::upgrade)
+        {
+            child.borrow_mut().mark_dirty();
+        }
+    }
 
 
-    fn get_model<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &ActorBase<PIPE_DATA, PIPE>) -> vecmath::Matrix4<f32>
+
+    // The world matrix as of the last update.
+    pub fn get_model(&self) -> vecmath::Matrix4<f32>
     {
-        base.model
+        self.world
     }
 
 
-    fn get_pos<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &ActorBase<PIPE_DATA, PIPE>) -> vecmath::Vector3<f32>
+    // Relative to the parent.
+    pub fn get_local_model(&self) -> vecmath::Matrix4<f32>
     {
-        base.positon
+        self.local
     }
 
 
+    pub fn get_pos(&self) -> vecmath::Vector3<f32>
+    {
+        self.transform.translation
+    }
 
-    fn move_position<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>, 
-                                                              pos:  vecmath::Vector3<f32>)  
+
+
+    pub fn get_transform(&self) -> Transform
     {
-        base.positon[0] += pos[0];
-        base.positon[1] += pos[1];
-        base.positon[2] += pos[2];
+        self.transform
+    }
+
+
 
-        base.not_worthy_of_update = false;
+    pub fn set_transform(&mut self, transform: Transform)
+    {
+        self.transform = transform;
+
+        self.mark_dirty();
     }
 
 
 
-    fn set_scale<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>, 
-                                                          scale: vecmath::Vector3<f32>)  
+    pub fn set_rotation(&mut self, rotation: Quaternion)
     {
-        base.scale[0] = scale[0];
-        base.scale[1] = scale[1];
-        base.scale[2] = scale[2];
+        self.transform.rotation = rotation;
 
-        base.not_worthy_of_update = false;
+        self.mark_dirty();
     }
 
 
-    fn set_position<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>, 
-                                                             pos: vecmath::Vector3<f32>)  
+
+    pub fn move_position(&mut self, pos: vecmath::Vector3<f32>)
     {
-        base.positon[0] = pos[0];
-        base.positon[1] = pos[1];
-        base.positon[2] = pos[2];
+        self.transform.translation = vecmath::vec3_add(self.transform.translation, pos);
 
-        base.not_worthy_of_update = false;
+        self.mark_dirty();
     }
 
 
 
-    fn rotate_x<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>, angle: f32)
+    pub fn set_scale(&mut self, scale: vecmath::Vector3<f32>)
     {
-        add_angle_with_overflow(&mut base.rotation_x, angle);
+        self.transform.scale = scale;
+
+        self.mark_dirty();
+    }
 
-        base.rotation_mat_x = crate::math::rotation_x(base.rotation_x);
 
-        base.not_worthy_of_update = false;
+    pub fn set_position(&mut self, pos: vecmath::Vector3<f32>)
+    {
+        self.transform.translation = pos;
+
+        self.mark_dirty();
     }
 
 
 
-    fn rotate_y<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>, angle: f32)
+    pub fn rotate_x(&mut self, angle: f32)
     {
-        add_angle_with_overflow(&mut base.rotation_y, angle);
+        self.rotate_local([ 1., 0., 0. ], angle);
+    }
 
-        base.rotation_mat_y = crate::math::rotation_y(base.rotation_y);
 
-        base.not_worthy_of_update = false;
+
+    pub fn rotate_y(&mut self, angle: f32)
+    {
+        self.rotate_local([ 0., 1., 0. ], angle);
     }
 
 
 
-    fn rotate_z<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>, angle: f32)
+    pub fn rotate_z(&mut self, angle: f32)
     {
-        add_angle_with_overflow(&mut base.rotation_z, angle);
+        self.rotate_local([ 0., 0., 1. ], angle);
+    }
+
 
-        base.rotation_mat_z = crate::math::rotation_z(base.rotation_z);
 
-        base.not_worthy_of_update = false;
+    // Turns around the actor's own axis clockwise looking down the axis, the way `math::rotation_x` does.
+    fn rotate_local(&mut self, axis: vecmath::Vector3<f32>, angle: f32)
+    {
+        self.transform.rotation = (self.transform.rotation * Quaternion::from_axis_angle(axis, -angle)).normalized();
+
+        self.mark_dirty();
     }
- 
 
 
-    fn update_actor_base<PIPE_DATA, PIPE: gfx::pso::PipelineInit>(base: &mut ActorBase<PIPE_DATA, PIPE>)
+
+    // Updates the parents first, their world matrices are part of this one.
+    pub fn update_world(&mut self)
     {
-        if base.not_worthy_of_update {
+        if !self.dirty {
             return;
         }
 
-        base.model = vecmath::mat4_id();
-        math::scale_translation(&mut base.model, base.scale);
+        self.local = self.transform.to_matrix();
+
+        self.world = match self.parent.as_ref().and_then(Weak::upgrade) {
+            Some(parent) => {
+                let mut parent = parent.borrow_mut();
+                parent.update_world();
+
+                vecmath::col_mat4_mul(parent.world, self.local)
+            },
+            None => self.local,
+        };
+
+        self.dirty = false;
+    }
+}
+
 
-        base.model = vecmath::col_mat4_mul(base.model, base.rotation_mat_x);
-        base.model = vecmath::col_mat4_mul(base.model, base.rotation_mat_z);
-        base.model = vecmath::col_mat4_mul(base.model, base.rotation_mat_y);
 
-        math::pos_translation(&mut base.model, base.positon);
+pub trait Actor
+{
+    fn initialize(mesh_desc: MeshDesc,
+                  renderer:  &mut Renderer) -> Result<Self, MeshError>
+        where Self: Sized;
+
 
-        base.not_worthy_of_update = true;
 
+    fn get_model(base: &ActorBase) -> vecmath::Matrix4<f32>
+    {
+        base.get_model()
     }
 
 
+    fn get_local_model(base: &ActorBase) -> vecmath::Matrix4<f32>
+    {
+        base.get_local_model()
+    }
+
+
+    fn get_pos(base: &ActorBase) -> vecmath::Vector3<f32>
+    {
+        base.get_pos()
+    }
+
+
+
+    fn get_transform(base: &ActorBase) -> Transform
+    {
+        base.get_transform()
+    }
+
+
+
+    fn set_transform(base: &mut ActorBase, transform: Transform)
+    {
+        base.set_transform(transform);
+    }
+
+
+
+    fn set_rotation(base: &mut ActorBase, rotation: Quaternion)
+    {
+        base.set_rotation(rotation);
+    }
+
+
+
+    fn move_position(base: &mut ActorBase, 
+                                                              pos:  vecmath::Vector3<f32>)  
+    {
+        base.move_position(pos);
+    }
+
+
+
+    fn set_scale(base: &mut ActorBase, 
+                                                          scale: vecmath::Vector3<f32>)  
+    {
+        base.set_scale(scale);
+    }
+
+
+    fn set_position(base: &mut ActorBase, 
+                                                             pos: vecmath::Vector3<f32>)  
+    {
+        base.set_position(pos);
+    }
+
+
+
+    fn rotate_x(base: &mut ActorBase, angle: f32)
+    {
+        base.rotate_x(angle);
+    }
 
-    fn update(&mut self);
 
 
-    fn resize(&mut self, window: &mut piston_window::PistonWindow);
+    fn rotate_y(base: &mut ActorBase, angle: f32)
+    {
+        base.rotate_y(angle);
+    }
+
+
+
+    fn rotate_z(base: &mut ActorBase, angle: f32)
+    {
+        base.rotate_z(angle);
+    }
+ 
+
 
+    fn update_actor_base(base: &mut ActorBase)
+    {
+        base.update_world();
+    }
 
-    fn render(&mut self,
-              window: &mut piston_window::PistonWindow,
-              camera: &vecmath::Matrix4<f32>,
-              projection: &vecmath::Matrix4<f32>);
+
+
+    fn update(&mut self);
 }
@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+
+use crate::{
+    rule_set_trait::{self, RuleSet},
+    solver::{self, AutoGameMove},
+    tower_state::{Peg, TowerState},
+};
+
+
+// Disks may only travel between neighbouring sticks, never straight between 1 and 3.
+pub struct AdjacentRules;
+
+
+
+impl RuleSet for AdjacentRules
+{
+    fn name(&self) -> &str
+    {
+        "adjacent"
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, false) && from.abs_diff(to) == 1
+    }
+
+
+    fn solve(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        let start = from.tower_peg()?;
+        let goal = to.tower_peg()?;
+        let mut moves = VecDeque::new();
+
+        solver::generate_adjacent_moves(&mut moves, from.disks_amount() as i32, start, goal);
+
+        Some(moves)
+    }
+
+
+    // Every disk triples the 3^n - 1 moves.
+    fn max_disks_amount(&self) -> i32
+    {
+        13
+    }
+}
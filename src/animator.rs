@@ -3,17 +3,18 @@ use std::cell::RefCell;
 use crate::{
     actor_trait::{Actor, ActorBase},
     donut_actor::ADonut,
+    transform::Transform,
 };
 
-type Vertex = crate::base_mesh_trait::pipe::Data<gfx_device_gl::Resources>;
-type Pipe = crate::base_mesh_trait::pipe::Init<'static>;
+const ANIMATION_STEPS: u32 = 100;
 
 pub struct Anmiator
 {
     is_in_animation: bool,
-    animated: Option<Rc<RefCell<ActorBase<Vertex, Pipe>>>>,
-    starting_pos: Option<vecmath::Vector3<f32>>,
-    target_pos: Option<vecmath::Vector3<f32>>,
+    animated: Option<Rc<RefCell<ActorBase>>>,
+    starting: Transform,
+    target: Transform,
+    steps_done: u32,
 }
 
 
@@ -22,11 +23,12 @@ impl Anmiator
 {
     pub fn new() -> Self
     {
-        Anmiator { 
+        Anmiator {
             is_in_animation: (false),
             animated: None,
-            starting_pos: None,
-            target_pos: None,
+            starting: Transform::identity(),
+            target: Transform::identity(),
+            steps_done: 0,
         }
     }
 
@@ -39,59 +41,42 @@ impl Anmiator
 
 
 
-    pub fn queue_animation(&mut self, 
-                           actor:        Rc<RefCell<ActorBase<Vertex, Pipe>>>,
-                           starting_pos: vecmath::Vector3<f32>,
-                           target_pos:   vecmath::Vector3<f32>)
+    // The actor moves, turns and scales from its current transform to `target`, relative to its parent.
+    pub fn queue_animation(&mut self,
+                           actor:  Rc<RefCell<ActorBase>>,
+                           target: Transform)
     {
-        self.animated = Some(actor.clone());
-        self.target_pos = Some(target_pos);
-        self.starting_pos = Some(starting_pos);
+        self.starting = <ADonut as Actor>::get_transform(&actor.borrow());
+        self.target = target;
+        self.animated = Some(actor);
+        self.steps_done = 0;
         self.is_in_animation = true;
     }
 
 
-    fn check_if_on_pos(&self) -> bool
-    {
-        let pos = <ADonut as Actor>::get_pos(&mut self.animated.clone().unwrap().borrow());
-        let target = self.target_pos.unwrap();
-
-        if !(pos[0] - 0.1 < target[0] && target[0] < pos[0] + 0.1) {
-            return false
-        }
-
-        if !(pos[1] - 0.1 < target[1] && target[1] < pos[1] + 0.1) {
-            return false
-        }
-
-        if !(pos[2] - 0.1 < target[2] && target[2] < pos[2] + 0.1) {
-            return false
-        }
-
-        true
-    }
-
-
     pub fn update(&mut self)
     {
-        if !self.is_in_animation || self.target_pos.is_none() || self.animated.is_none() {
+        if !self.is_in_animation {
             return
         }
-    
-        let base = self.animated.clone().unwrap();
 
-        let pos_diff = [ (self.target_pos.unwrap()[0] - self.starting_pos.unwrap()[0]) * 0.01,
-                         (self.target_pos.unwrap()[1] - self.starting_pos.unwrap()[1]) * 0.01,
-                         (self.target_pos.unwrap()[2] - self.starting_pos.unwrap()[2]) * 0.01 ];
+        let Some(base) = self.animated.clone() else {
+            return
+        };
 
-        <ADonut as Actor>::move_position(&mut base.borrow_mut(), pos_diff);
+        self.steps_done += 1;
 
-        if self.check_if_on_pos() 
+        let transform = match self.steps_done >= ANIMATION_STEPS {
+            true => self.target,
+            false => self.starting.slerp(&self.target, self.steps_done as f32 / ANIMATION_STEPS as f32),
+        };
+
+        <ADonut as Actor>::set_transform(&mut base.borrow_mut(), transform);
+
+        if self.steps_done >= ANIMATION_STEPS
         {
             self.is_in_animation = false;
             self.animated = None;
-            self.target_pos = None;
         }
     }
 }
-
@@ -0,0 +1,94 @@
+use crate::{base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// Flat arrow lying in the XZ plane, it starts at the origin and points along +Z.
+pub struct ArrowMeshFactory
+{
+    pub length: f32,
+    pub width: f32,
+    pub thickness: f32,
+}
+
+
+
+impl ArrowMeshFactory 
+{
+    pub fn new(length: f32, width: f32) -> Self
+    {
+        ArrowMeshFactory {
+            length,
+            width,
+            thickness: 0.1,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for ArrowMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Arrow {
+            length: self.length,
+            width: self.width,
+            thickness: self.thickness,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for ArrowMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let shaft = self.width * 0.4;
+        let head_length = (self.width * 1.5).min(self.length * 0.5);
+        let neck = self.length - head_length;
+
+        let outline = [ [ -shaft, 0. ],
+                        [  shaft, 0. ],
+                        [  shaft, neck ],
+                        [  self.width, neck ],
+                        [  0., self.length ],
+                        [ -self.width, neck ],
+                        [ -shaft, neck ] ];
+
+        let mut vertex_data = Vec::new();
+        let mut index_data = Vec::new();
+
+        for y in [ self.thickness * 0.5, -self.thickness * 0.5 ]
+        {
+            for [x, z] in outline
+            {
+                vertex_data.push(base_mesh_trait::Vertex::new([x, y, z]));
+            }
+        }
+
+        let bottom = outline.len() as u32;
+
+        for [a, b, c] in [ [0, 1, 2], [2, 6, 0], [5, 3, 4] ]
+        {
+            index_data.extend_from_slice(&[c, b, a]);
+            index_data.extend_from_slice(&[bottom + a, bottom + b, bottom + c]);
+        }
+
+        for i in 0..bottom
+        {
+            let next = (i + 1) % bottom;
+
+            index_data.extend_from_slice(&[i, next, bottom + next, bottom + next, bottom + i, i]);
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+
+use crate::tower_state::{Disk, TowerState, PEGS_AMOUNT};
+
+
+// A disk of width w is w characters on both sides of the peg, grouped disks show their group on the peg.
+fn disk_text(disk: &Disk, grouped: bool) -> String
+{
+    let side = "=".repeat(disk.width as usize);
+    let center = match grouped {
+        true => (b'a' + disk.group) as char,
+        false => '|',
+    };
+
+    format!("{}{}{}", side, center, side)
+}
+
+
+
+/// The pegs and their disks as ASCII art, with the stick numbers under the base.
+pub fn draw_towers(state: &TowerState) -> String
+{
+    let grouped = state.pegs.iter().flatten().any(|disk| disk.group > 0);
+    let widest = state.pegs.iter().flatten().map(|disk| disk.width as usize).max().unwrap_or(1);
+    let height = state.pegs.iter().map(|disks| disks.len()).max().unwrap_or(0) + 1;
+    let column = widest * 2 + 3;
+
+    let mut lines = Vec::new();
+
+    for level in (0..height).rev()
+    {
+        let row: String = state.pegs
+                               .iter()
+                               .map(|disks| {
+                                   let text = disks.get(level).map_or("|".to_string(), |disk| disk_text(disk, grouped));
+                                   format!("{:^column$}", text)
+                               })
+                               .collect();
+
+        lines.push(row.trim_end().to_string());
+    }
+
+    lines.push("-".repeat(column * PEGS_AMOUNT));
+    lines.push((1..=PEGS_AMOUNT).map(|peg| format!("{:^column$}", peg)).collect::<String>().trim_end().to_string());
+
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,45 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use crate::{actor_trait::ActorBase, base_mesh_trait::MeshDesc, mesh_error::MeshError, renderer::Renderer};
+
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+pub struct ABall
+{
+    pub actor_base: Rc<RefCell<ActorBase>>,
+    pub ball_color: u8,
+}
+
+
+
+
+
+impl crate::actor_trait::Actor for ABall
+{
+    fn initialize(mesh_desc: MeshDesc,
+                  renderer:  &mut Renderer) -> Result<Self, MeshError>
+    {
+        let mesh = renderer.mesh(&mesh_desc)?;
+
+        Ok(ABall {
+            actor_base: (Rc::new(RefCell::new(ActorBase::new(mesh)))),
+            ball_color: 0,
+        })
+    }
+
+
+
+    fn update(&mut self)
+    {
+        Self::update_actor_base(&mut self.actor_base.borrow_mut());
+    }
+}
+
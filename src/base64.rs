@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+
+const DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+
+
+pub fn encode(bytes: &[u8]) -> String
+{
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3)
+    {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| group | (*byte as u32) << (16 - 8 * i));
+
+        for i in 0..4
+        {
+            match i <= chunk.len() {
+                true => text.push(DIGITS[(group >> (18 - 6 * i) & 63) as usize] as char),
+                false => text.push('='),
+            }
+        }
+    }
+
+    text
+}
+
+
+
+/// `None` when the text has characters outside of the base64 alphabet.
+pub fn decode(text: &str) -> Option<Vec<u8>>
+{
+    let digits: Vec<u32> = text.bytes()
+                               .filter(|byte| !byte.is_ascii_whitespace() && *byte != b'=')
+                               .map(|byte| DIGITS.iter().position(|digit| *digit == byte).map(|value| value as u32))
+                               .collect::<Option<_>>()?;
+    let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+
+    for chunk in digits.chunks(4)
+    {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, digit)| group | digit << (18 - 6 * i));
+
+        for i in 0..chunk.len().saturating_sub(1)
+        {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Some(bytes)
+}
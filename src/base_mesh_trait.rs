@@ -6,17 +6,9 @@ extern crate camera_controllers;
 extern crate gfx;
 extern crate shader_version;
 
-use gfx::IntoIndexBuffer;
-
-use gfx::{
-    Factory,
-    traits::FactoryExt,
-};
-use opengl_graphics::GLSL;
 use rand::Rng;
-use shader_version::Shaders;
-
 
+use crate::mesh_error::MeshError;
 
 
 
@@ -24,91 +16,193 @@ gfx_vertex_struct!(
     Vertex 
     {
         a_pos: [f32; 4] = "a_pos",
+        a_normal: [f32; 3] = "a_normal",
     }
 );
 
 impl Vertex 
 {
+    // The normal is filled in by `MeshData::new` from the triangles around the vertex.
     pub fn new(pos: [f32; 3]) -> Vertex 
     {
         Vertex {
             a_pos: [pos[0], pos[1], pos[2], 1.],
+            a_normal: [ 0., 0., 0. ],
         }
     }
 }
 
-gfx_pipeline!( 
-    pipe 
+
+
+/// Moves `pos` away from the Z axis by `growth`, like the vertex shader widens ring meshes.
+pub fn grow_ring(pos: [f32; 4], growth: f32) -> [f32; 4]
+{
+    let distance = (pos[0] * pos[0] + pos[1] * pos[1]).sqrt();
+
+    if growth == 0. || distance == 0. {
+        return pos;
+    }
+
+    let scale = (distance + growth) / distance;
+
+    [ pos[0] * scale, pos[1] * scale, pos[2], pos[3] ]
+}
+
+
+
+// Per-instance attributes of the instanced pipeline: the model matrix by columns, the colour
+// and how much a ring mesh is widened, see `grow_ring`.
+gfx_vertex_struct!( 
+    Instance 
     {
-        vbuf:              gfx::VertexBuffer<Vertex>                     = (),
-        u_model_view_proj: gfx::Global<[[f32; 4]; 4]>                    = "u_model_view_proj",
-        t_color:           gfx::Global<[f32; 4]>                         = "t_color",
-        out_color:         gfx::RenderTarget<::gfx::format::Srgba8>      = "o_Color",
-        out_depth:         gfx::DepthTarget<::gfx::format::DepthStencil> = gfx::preset::depth::LESS_EQUAL_WRITE,
+        i_model_0: [f32; 4] = "i_model_0",
+        i_model_1: [f32; 4] = "i_model_1",
+        i_model_2: [f32; 4] = "i_model_2",
+        i_model_3: [f32; 4] = "i_model_3",
+        i_color:   [f32; 4] = "i_color",
+        i_ring_growth: f32 = "i_ring_growth",
     }
 );
 
+impl Instance 
+{
+    pub fn new(model: vecmath::Matrix4<f32>, color: [f32; 4], ring_growth: f32) -> Instance 
+    {
+        Instance {
+            i_model_0: model[0],
+            i_model_1: model[1],
+            i_model_2: model[2],
+            i_model_3: model[3],
+            i_color: color,
+            i_ring_growth: ring_growth,
+        }
+    }
+}
 
 
-
-pub struct BasicMeshOnGpu
+/// Triangles built on the CPU, before they are uploaded.
+#[derive(Clone, Debug)]
+pub struct MeshData
 {
-    pub vbuf:         gfx::handle::Buffer<gfx_device_gl::Resources, Vertex>,
-    pub color:        [f32; 4], 
-    pub slice:        gfx::Slice<gfx_device_gl::Resources>,
-    pub pso:          gfx::PipelineState<gfx_device_gl::Resources, 
-                                         pipe::Meta>,
-    pub sampler:      gfx::handle::Sampler<gfx_device_gl::Resources>
+    pub vertices: Vec<Vertex>,
+    pub indices:  Vec<u32>,
 }
 
 
 
-impl BasicMeshOnGpu
+impl MeshData
 {
-    pub fn new_from_vertices(open_gl:       &crate::piston_window::OpenGL,
-                             factory:       &mut gfx_device_gl::Factory,
-                             pipe:          pipe::Init,
-                             vertex_buffer: &Vec::<Vertex>,
-                             index_buffer:  &Vec::<u16>) -> BasicMeshOnGpu
-    {   
-        let index_buffer = index_buffer.into_index_buffer(factory);
-        let (vbuf, slice) = factory.create_vertex_buffer_with_slice(&vertex_buffer, index_buffer);
-    
-        let mut random = rand::thread_rng();
-        let r = random.gen_range(0. .. 1.0);
-        let g = random.gen_range(0. .. 1.0);
-        let b = random.gen_range(0. .. 1.0);
-        let color = [ r, g, b, 1.0 ];
-
-        let sinfo = gfx::texture::SamplerInfo::new(gfx::texture::FilterMethod::Bilinear,
-                                                   gfx::texture::WrapMode::Clamp);
-        
-        let glsl = open_gl.to_glsl();
-        let pso = factory.create_pipeline_simple(Shaders::new()
-                                                          .set(GLSL::V1_50, include_str!("../assets/shader_150.vert"))
-                                                          .get(glsl).unwrap().as_bytes(),
-                                                 Shaders::new()
-                                                          .set(GLSL::V1_50, include_str!("../assets/shader_150.frag"))
-                                                          .get(glsl).unwrap().as_bytes(),
-                                                 pipe).unwrap();
-    
-    
-        BasicMeshOnGpu { 
-            vbuf: (vbuf),
-            slice: (slice),
-            color: (color),
-            pso: (pso),
-            sampler: (factory.create_sampler(sinfo)) 
+    /// Checks that the indices form whole triangles of existing vertices and gives every vertex
+    /// the normal of the triangles around it, the bigger ones weigh more.
+    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Result<Self, MeshError>
+    {
+        if u32::try_from(vertices.len()).is_err() {
+            return Err(MeshError::TooManyVertices { vertices: vertices.len() });
         }
+
+        if indices.is_empty() {
+            return Err(MeshError::EmptyMesh);
+        }
+
+        if !indices.len().is_multiple_of(3) {
+            return Err(MeshError::IncompleteTriangle { indices: indices.len() });
+        }
+
+        if let Some(index) = indices.iter().find(|index| **index as usize >= vertices.len()) {
+            return Err(MeshError::IndexOutOfRange { index: *index, vertices: vertices.len() });
+        }
+
+        let mut vertices = vertices;
+        let mut normals = vec![ [ 0., 0., 0. ]; vertices.len() ];
+
+        for triangle in indices.chunks_exact(3)
+        {
+            let [a, b, c] = [ 0, 1, 2 ].map(|i| {
+                let pos = vertices[triangle[i] as usize].a_pos;
+                [ pos[0], pos[1], pos[2] ]
+            });
+            let face = vecmath::vec3_cross(vecmath::vec3_sub(b, a), vecmath::vec3_sub(c, a));
+
+            for index in triangle
+            {
+                normals[*index as usize] = vecmath::vec3_add(normals[*index as usize], face);
+            }
+        }
+
+        for (vertex, normal) in vertices.iter_mut().zip(normals)
+        {
+            let length = vecmath::vec3_len(normal);
+
+            vertex.a_normal = match length > 0. {
+                true => vecmath::vec3_scale(normal, 1. / length),
+                false => [ 0., 0., 1. ],
+            };
+        }
+
+        Ok(MeshData { vertices, indices })
+    }
+
+
+
+    /// The mesh widened the way `grow_ring` moves its vertices, the normals stay.
+    pub fn grown(&self, growth: f32) -> MeshData
+    {
+        let vertices = self.vertices
+                           .iter()
+                           .map(|vertex| Vertex { a_pos: grow_ring(vertex.a_pos, growth), ..*vertex })
+                           .collect();
+
+        MeshData { vertices, indices: self.indices.clone() }
+    }
+
+
+
+    /// The smallest and the largest coordinates of the vertices.
+    pub fn bounds(&self) -> ([f32; 3], [f32; 3])
+    {
+        let mut min = [ f32::INFINITY; 3 ];
+        let mut max = [ f32::NEG_INFINITY; 3 ];
+
+        for vertex in self.vertices.iter()
+        {
+            for axis in 0..3
+            {
+                min[axis] = min[axis].min(vertex.a_pos[axis]);
+                max[axis] = max[axis].max(vertex.a_pos[axis]);
+            }
+        }
+
+        (min, max)
+    }
+
+
+
+    /// Distance of the farthest vertex from the mesh origin.
+    pub fn bounding_radius(&self) -> f32
+    {
+        self.vertices
+            .iter()
+            .map(|vertex| vecmath::vec3_len([ vertex.a_pos[0], vertex.a_pos[1], vertex.a_pos[2] ]))
+            .fold(0., f32::max)
     }
 }
 
 
-pub struct MeshDesc
+
+pub fn random_color() -> [f32; 4]
 {
-    pub desc: vecmath::Vector4<f32>,
+    let mut random = rand::thread_rng();
+    let r = random.gen_range(0. .. 1.0);
+    let g = random.gen_range(0. .. 1.0);
+    let b = random.gen_range(0. .. 1.0);
+
+    [ r, g, b, 1.0 ]
 }
 
+
+pub use crate::mesh_desc::MeshDesc;
+
+#[allow(clippy::wrong_self_convention)]
 pub trait IntoDesc
 {
     fn into_desc(&self) -> MeshDesc;
@@ -117,7 +211,5 @@ pub trait IntoDesc
 
 pub trait MeshFactory
 {
-    fn create_mesh_instance_on_gpu(&self, 
-                                   open_gl: &crate::piston_window::OpenGL,
-                                   factory: &mut gfx_device_gl::Factory) -> BasicMeshOnGpu;
+    fn build_mesh(&self) -> Result<MeshData, MeshError>;
 }
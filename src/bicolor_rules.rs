@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+
+use crate::{
+    game_settings::GameSettings,
+    rule_set_trait::{self, RuleSet},
+    solver::{self, AutoGameMove},
+    tower_state::{Disk, Peg, TowerState, PEGS_AMOUNT},
+};
+
+
+// Towers up to this many disks are solved by the breadth first search.
+const SEARCHED_DISKS_AMOUNT: usize = 10;
+
+const GROUP_COLORS: [[f32; 4]; 3] = [ [ 0.9, 0.9, 0.85, 1.0 ],
+                                      [ 0.8, 0.1, 0.1, 1.0 ],
+                                      [ 0.1, 0.3, 0.9, 1.0 ] ];
+
+
+// Every width comes once per colour group in one interleaved tower, the goal is to sort
+// the colours into separate towers. Disks of the same width may be stacked.
+pub struct BicolorRules
+{
+    pub groups: u8,
+}
+
+
+
+impl RuleSet for BicolorRules
+{
+    fn name(&self) -> &str
+    {
+        match self.groups
+        {
+            2 => "bicolor",
+            _ => "tricolor",
+        }
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, true)
+    }
+
+
+    fn allows_equal_widths(&self) -> bool
+    {
+        true
+    }
+
+
+    // Every group brings one disk of each width, there is no room for chosen sizes.
+    fn uses_disk_sizes(&self) -> bool
+    {
+        false
+    }
+
+
+    // Only the sorting of the whole starting tower has a dedicated solver. It moves the narrower
+    // disks as one block, which isn't always optimal, so small towers are left to the search.
+    fn solve(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        if from.disks_amount() <= SEARCHED_DISKS_AMOUNT {
+            return None
+        }
+
+        solver::moves_by_sorting(from, to)
+    }
+
+
+    // Counted in widths, every width brings a disk per group.
+    fn max_disks_amount(&self) -> i32
+    {
+        match self.groups
+        {
+            2 => 18,
+            _ => 16,
+        }
+    }
+
+
+    fn start_state(&self, settings: &GameSettings) -> TowerState
+    {
+        TowerState::interleaved(settings.dounuts_amount, self.groups, settings.start_peg)
+    }
+
+
+    // The first group is sorted onto the goal stick and the others onto the sticks after it,
+    // the start stick is taken last.
+    fn goal_state(&self, settings: &GameSettings) -> TowerState
+    {
+        let mut pegs: Vec<Peg> = (0..PEGS_AMOUNT).map(|i| (settings.goal_peg + i) % PEGS_AMOUNT).collect();
+        pegs.sort_by_key(|peg| *peg != settings.goal_peg && *peg == settings.start_peg);
+
+        let mut state = TowerState::empty();
+
+        for (group, peg) in (0..self.groups).zip(pegs)
+        {
+            state.pegs[peg] = (1..=settings.dounuts_amount).rev()
+                                                           .map(|width| Disk { group, ..Disk::new(width) })
+                                                           .collect();
+        }
+
+        state
+    }
+
+
+    fn donut_color(&self, disk: &Disk) -> Option<[f32; 4]>
+    {
+        GROUP_COLORS.get(disk.group as usize).copied()
+    }
+}
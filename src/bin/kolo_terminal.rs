@@ -0,0 +1,121 @@
+use std::io::{self, BufRead, Write};
+
+use piston::{Button, Key};
+use kolo::{
+    ascii_view,
+    game_settings,
+    master_trait::{self, Master},
+    renderer,
+};
+
+
+// Plays the game in a terminal, e.g. over SSH: the same masters and settings as the 3D game,
+// read line by line from stdin and drawn as ASCII art after every line.
+
+
+// Runs the master until it waits for input again, the 3D game updates it once a frame instead.
+// Every move finished on the way is drawn, so the moves of the auto gamer show one by one.
+// Tells whether anything was drawn.
+fn settle(master: &mut dyn Master, out: &mut impl Write) -> io::Result<bool>
+{
+    let mut drawn = false;
+    let mut state = master.current_state();
+
+    loop
+    {
+        // The update after the last busy one only checks the finished move for a win, or starts the next one of the auto gamer.
+        let busy = master.is_busy();
+        master.update(None);
+
+        if master.current_state() != state {
+            state = master.current_state();
+            draw(master, out)?;
+            drawn = true;
+        }
+
+        if !busy && !master.is_busy() {
+            return Ok(drawn);
+        }
+    }
+}
+
+
+
+fn draw(master: &dyn Master, out: &mut impl Write) -> io::Result<()>
+{
+    let statistics = master.statistics();
+
+    write!(out, "\n{}", ascii_view::draw_towers(&master.current_state()))?;
+    writeln!(out, "moves: {}, time: {:.1}s", statistics.moves, statistics.elapsed().as_secs_f32())?;
+
+    out.flush()
+}
+
+
+
+fn play(out: &mut impl Write) -> io::Result<()>
+{
+    let settings = game_settings::GameSettings::from_args(std::env::args());
+    let mut renderer = renderer::Renderer::new();
+
+    let mut master = match master_trait::create_master(&settings, &mut renderer)
+    {
+        Ok(master) => master,
+        Err(error) => return writeln!(out, "Can't build the scene: {}", error),
+    };
+
+    writeln!(out, "Type two stick numbers like 13 to move from the first stick to the second, h for a hint, \
+                   a to let the game finish itself and q to quit.")?;
+    settle(master.as_mut(), out)?;
+    draw(master.as_ref(), out)?;
+
+    for line in std::io::stdin().lock().lines()
+    {
+        let Ok(line) = line else {
+            break;
+        };
+        let mut drawn = false;
+
+        for command in line.chars().filter(|command| !command.is_whitespace())
+        {
+            match command.to_ascii_lowercase()
+            {
+                'q' => return Ok(()),
+                key @ ('1'..='3' | 'a' | 'h') => {
+                    master.update(Some(Button::Keyboard(Key::from(key as u32))));
+                    drawn |= settle(master.as_mut(), out)?;
+                },
+                _ => writeln!(out, "Unknown command {:?}.", command)?,
+            }
+        }
+
+        // Picking a stick or a hint changes nothing on the towers, they are shown again all the same.
+        if !drawn {
+            draw(master.as_ref(), out)?;
+        }
+
+        if !master.is_playing() {
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
+
+
+fn main()
+{
+    // The masters print their messages with println!, which panics once the reader went away, like `head` does.
+    // Like other command line tools the game then just ends.
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+
+    // Elsewhere a reader that went away shows up as an error of the writes.
+    if let Err(error) = play(&mut std::io::stdout().lock()) && error.kind() != io::ErrorKind::BrokenPipe {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,76 @@
+#![allow(dead_code)]
+
+use crate::{base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// Axis aligned box centred at the origin.
+pub struct BoxMeshFactory
+{
+    pub width: f32,
+    pub height: f32,
+    pub depth: f32,
+}
+
+
+
+impl BoxMeshFactory 
+{
+    pub fn new(width: f32, height: f32, depth: f32) -> Self
+    {
+        BoxMeshFactory {
+            width,
+            height,
+            depth,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for BoxMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Box {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for BoxMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let x = self.width * 0.5;
+        let y = self.height * 0.5;
+        let z = self.depth * 0.5;
+
+        let vertex_data: Vec<base_mesh_trait::Vertex> = [ [ -x, -y, -z ], [ x, -y, -z ], [ x, y, -z ], [ -x, y, -z ],
+                                                          [ -x, -y,  z ], [ x, -y,  z ], [ x, y,  z ], [ -x, y,  z ] ]
+                                                        .into_iter()
+                                                        .map(base_mesh_trait::Vertex::new)
+                                                        .collect();
+
+        // Two triangles per face, wound counter clockwise seen from the outside.
+        let index_data = vec![ 0, 3, 2, 2, 1, 0,
+                               4, 5, 6, 6, 7, 4,
+                               0, 1, 5, 5, 4, 0,
+                               3, 7, 6, 6, 2, 3,
+                               0, 4, 7, 7, 3, 0,
+                               1, 2, 6, 6, 5, 1 ];
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+use crate::{
+    rule_set_trait::{self, RuleSet},
+    solver::{self, AutoGameMove},
+    tower_state::{Peg, TowerState},
+};
+
+
+pub struct ClassicRules;
+
+
+
+impl RuleSet for ClassicRules
+{
+    fn name(&self) -> &str
+    {
+        "classic"
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, false)
+    }
+
+
+    fn solve(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        let mut moves = VecDeque::new();
+        solver::moves_between(&mut moves, &from.assignment(), &to.assignment());
+
+        Some(moves)
+    }
+
+
+    fn next_moves(&self, from: &TowerState, to: &TowerState, limit: usize) -> Option<VecDeque<AutoGameMove>>
+    {
+        let mut current = from.assignment();
+        let goal = to.assignment();
+        let mut moves = VecDeque::new();
+
+        while moves.len() < limit
+        {
+            let Some(game_move) = solver::next_move_between(&current, &goal) else {
+                break;
+            };
+
+            // The narrowest disk on the stick is the one on top.
+            let moved = current.iter().position(|peg| *peg == game_move.from).unwrap();
+            current[moved] = game_move.to;
+            moves.push_back(game_move);
+        }
+
+        Some(moves)
+    }
+
+
+    fn distance(&self, from: &TowerState, to: &TowerState) -> Option<u64>
+    {
+        Some(solver::distance_between(&from.assignment(), &to.assignment()))
+    }
+
+
+    // Past 64 disks the distances no longer fit in a u64.
+    fn max_disks_amount(&self) -> i32
+    {
+        64
+    }
+
+
+    // The difficulty is measured from the full tower on the start stick,
+    // a scrambled start only changes how far the player actually is.
+    fn random_target(&self, start: &TowerState, start_peg: Peg, distance: u64) -> TowerState
+    {
+        let assignment = solver::random_assignment_at_distance(start.disks_amount(), start_peg, distance);
+
+        TowerState::from_assignment(&assignment)
+    }
+}
@@ -0,0 +1,87 @@
+#![allow(dead_code)]
+
+use crate::{math, base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// Cone standing on a closed base in the XY plane, the apex points along +Z.
+pub struct ConeMeshFactory
+{
+    pub radius: f32,
+    pub height: f32,
+    pub segments: isize,
+}
+
+
+
+impl ConeMeshFactory 
+{
+    pub fn new(radius: f32, height: f32) -> Self
+    {
+        ConeMeshFactory {
+            radius,
+            height,
+            segments: 32,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for ConeMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Cone {
+            radius: self.radius,
+            height: self.height,
+            segments: self.segments,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for ConeMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let mut vertex_data = Vec::new();
+        let mut index_data = Vec::new();
+
+        let segments = self.segments;
+
+        for j in 0..segments 
+        {
+            let theta = (j as f32 / segments as f32) * math::TWO_PI;
+
+            vertex_data.push(base_mesh_trait::Vertex::new([ self.radius * theta.cos(),
+                                                            self.radius * theta.sin(),
+                                                            0. ]));
+        }
+
+        let base_centre = segments as u32;
+        let apex = base_centre + 1;
+
+        vertex_data.push(base_mesh_trait::Vertex::new([ 0., 0., 0. ]));
+        vertex_data.push(base_mesh_trait::Vertex::new([ 0., 0., self.height ]));
+
+        for j in 0..segments 
+        {
+            let a = j as u32;
+            let b = ((j + 1) % segments) as u32;
+
+            index_data.extend_from_slice(&[a, b, apex]);
+            index_data.extend_from_slice(&[base_centre, b, a]);
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
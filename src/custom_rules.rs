@@ -0,0 +1,99 @@
+#![allow(dead_code)]
+
+use crate::{
+    rule_set_trait::{self, RuleSet},
+    solver::AutoGameMove,
+    tower_state::{Peg, TowerState, PEGS_AMOUNT},
+};
+
+
+type MoveCheck = dyn Fn(&TowerState, Peg, Peg) -> bool;
+
+
+// Rules put together at run time, either from any move check or from a list of allowed moves.
+pub struct CustomRules
+{
+    name: String,
+    check: Box<MoveCheck>,
+    directions: Option<Vec<AutoGameMove>>,
+}
+
+
+
+impl CustomRules
+{
+    pub fn new<F>(name: &str, check: F) -> Self
+        where F: Fn(&TowerState, Peg, Peg) -> bool + 'static
+    {
+        CustomRules {
+            name: name.to_string(),
+            check: Box::new(check),
+            directions: None,
+        }
+    }
+
+
+
+    /// Classic width rule limited to the listed moves.
+    pub fn from_directions(name: &str, directions: Vec<AutoGameMove>) -> Self
+    {
+        let allowed = directions.clone();
+
+        CustomRules {
+            name: name.to_string(),
+            check: Box::new(move |state, from, to| {
+                rule_set_trait::fits_by_width(state, from, to, false)
+                    && allowed.contains(&AutoGameMove { from, to })
+            }),
+            directions: Some(directions),
+        }
+    }
+
+
+
+    /// Parses the allowed moves from stick number pairs, "12,23,31" is the cyclic variant.
+    pub fn from_spec(name: &str, spec: &str) -> Option<Self>
+    {
+        let mut directions = Vec::new();
+
+        for pair in spec.split(',')
+        {
+            let digits: Vec<Peg> = pair.trim()
+                                       .chars()
+                                       .map(|c| c.to_digit(10).map(|d| d as Peg))
+                                       .collect::<Option<_>>()?;
+
+            match digits[..]
+            {
+                [ from, to ] if from != to && (1..=PEGS_AMOUNT).contains(&from) && (1..=PEGS_AMOUNT).contains(&to) => {
+                    directions.push(AutoGameMove { from: from - 1, to: to - 1 });
+                },
+                _ => return None,
+            }
+        }
+
+        Some(CustomRules::from_directions(name, directions))
+    }
+}
+
+
+
+impl RuleSet for CustomRules
+{
+    fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        (self.check)(state, from, to)
+    }
+
+
+    fn allowed_directions(&self) -> Option<Vec<AutoGameMove>>
+    {
+        self.directions.clone()
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+
+use crate::{
+    rule_set_trait::{self, RuleSet},
+    solver::{self, AutoGameMove},
+    tower_state::{Peg, TowerState, PEGS_AMOUNT},
+};
+
+
+// Disks may only travel clockwise, from stick i to stick i + 1 (mod 3).
+pub struct CyclicRules;
+
+
+
+impl RuleSet for CyclicRules
+{
+    fn name(&self) -> &str
+    {
+        "cyclic"
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, false) && to == (from + 1) % PEGS_AMOUNT
+    }
+
+
+    fn solve(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        let start = from.tower_peg()?;
+        let goal = to.tower_peg()?;
+        let n = from.disks_amount() as i32;
+        let mut moves = VecDeque::new();
+
+        match (goal + PEGS_AMOUNT - start) % PEGS_AMOUNT
+        {
+            1 => solver::generate_cyclic_moves_one(&mut moves, n, start),
+            2 => solver::generate_cyclic_moves_two(&mut moves, n, start),
+            _ => (),
+        }
+
+        Some(moves)
+    }
+
+
+    // Fourteen disks already take over a million moves.
+    fn max_disks_amount(&self) -> i32
+    {
+        14
+    }
+
+
+    fn allowed_directions(&self) -> Option<Vec<AutoGameMove>>
+    {
+        Some((0..PEGS_AMOUNT).map(|from| AutoGameMove { from, to: (from + 1) % PEGS_AMOUNT }).collect())
+    }
+}
@@ -0,0 +1,103 @@
+#![allow(dead_code)]
+
+use crate::{math, base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// Cylinder along the Z axis centred at the origin, without `caps` it is an open tube.
+pub struct CylinderMeshFactory
+{
+    pub radius: f32,
+    pub height: f32,
+    pub segments: isize,
+    pub caps: bool,
+}
+
+
+
+impl CylinderMeshFactory 
+{
+    pub fn new(radius: f32, height: f32) -> Self
+    {
+        CylinderMeshFactory {
+            radius,
+            height,
+            segments: 32,
+            caps: true,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for CylinderMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Cylinder {
+            radius: self.radius,
+            height: self.height,
+            segments: self.segments,
+            caps: self.caps,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for CylinderMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let mut vertex_data = Vec::new();
+        let mut index_data = Vec::new();
+
+        let segments = self.segments;
+        let half_height = self.height * 0.5;
+
+        // Ring 0 is the bottom edge, ring 1 the top edge, the two last vertices are the cap centres.
+        for z in [ -half_height, half_height ]
+        {
+            for j in 0..segments 
+            {
+                let theta = (j as f32 / segments as f32) * math::TWO_PI;
+
+                vertex_data.push(base_mesh_trait::Vertex::new([ self.radius * theta.cos(),
+                                                                self.radius * theta.sin(),
+                                                                z ]));
+            }
+        }
+
+        let bottom_centre = (2 * segments) as u32;
+        let top_centre = bottom_centre + 1;
+
+        vertex_data.push(base_mesh_trait::Vertex::new([ 0., 0., -half_height ]));
+        vertex_data.push(base_mesh_trait::Vertex::new([ 0., 0., half_height ]));
+
+        for j in 0..segments 
+        {
+            let next_j = (j + 1) % segments;
+
+            let a = j as u32;
+            let b = next_j as u32;
+            let c = (segments + next_j) as u32;
+            let d = (segments + j) as u32;
+
+            index_data.extend_from_slice(&[a, b, c, c, d, a]);
+
+            if self.caps {
+                index_data.extend_from_slice(&[bottom_centre, b, a]);
+                index_data.extend_from_slice(&[top_centre, d, c]);
+            }
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
@@ -1,10 +1,6 @@
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::{actor_trait::ActorBase, donut_mesh::DonutMeshFactory, base_mesh_trait::{MeshDesc, MeshFactory}};
-use camera_controllers::{
-    model_view_projection
-};
-use vecmath::Matrix4;
+use crate::{actor_trait::ActorBase, base_mesh_trait::MeshDesc, mesh_error::MeshError, renderer::Renderer, tower_state::Disk};
 
 
 
@@ -20,72 +16,51 @@ extern crate shader_version;
 
 
 
-type Vertex = crate::base_mesh_trait::pipe::Data<gfx_device_gl::Resources>;
-type Pipe = crate::base_mesh_trait::pipe::Init<'static>;
-
 pub struct ADonut
 {
-    pub actor_base: Rc<RefCell<ActorBase<Vertex, Pipe>>>,
+    pub actor_base: Rc<RefCell<ActorBase>>,
     pub donut_width: i32,
+    pub donut_group: u8,
+    pub north_up: bool,
 }
 
 
 
 
 
-impl crate::actor_trait::Actor for ADonut
+impl ADonut
 {
-    fn initialize(mesh_desc: MeshDesc,
-                  open_gl:   &crate::piston_window::OpenGL, 
-                  window:    &piston_window::PistonWindow,
-                  factory:   &mut gfx_device_gl::Factory) -> Self
+    pub fn as_disk(&self) -> Disk
     {
-        let donut_mesh = DonutMeshFactory::new(mesh_desc.desc[0], mesh_desc.desc[1]);
-
-        let mesh = donut_mesh.create_mesh_instance_on_gpu(open_gl, factory);
-        let mesh_data = crate::base_mesh_trait::pipe::Data {
-            vbuf: mesh.vbuf,
-            u_model_view_proj: vecmath::mat4_id(),
-            t_color: mesh.color,
-            out_color: window.output_color.clone(),
-            out_depth: window.output_stencil.clone(),
-        };
-        
-        ADonut { 
-            actor_base: (Rc::new(RefCell::new(ActorBase::new(mesh_data, mesh.slice, mesh.pso)))),
-            donut_width: 0,
+        Disk {
+            width: self.donut_width,
+            group: self.donut_group,
+            north_up: self.north_up,
         }
     }
+}
 
 
 
-    fn resize(&mut self, window: &mut piston_window::PistonWindow)
+impl crate::actor_trait::Actor for ADonut
+{
+    fn initialize(mesh_desc: MeshDesc,
+                  renderer:  &mut Renderer) -> Result<Self, MeshError>
     {
-        self.actor_base.borrow_mut().mesh_data.out_depth = window.output_stencil.clone();
-        self.actor_base.borrow_mut().mesh_data.out_color = window.output_color.clone();
-    }
- 
-
+        let mesh = renderer.mesh(&mesh_desc)?;
 
-    fn update(&mut self)
-    {
-        Self::update_actor_base(&mut self.actor_base.borrow_mut());
+        Ok(ADonut {
+            actor_base: (Rc::new(RefCell::new(ActorBase::new(mesh)))),
+            donut_width: 0,
+            donut_group: 0,
+            north_up: true,
+        })
     }
 
 
 
-    fn render(&mut self,
-              window:     &mut piston_window::PistonWindow,
-              camera:     &Matrix4<f32>,
-              projection: &vecmath::Matrix4<f32>) 
+    fn update(&mut self)
     {
-        let model = ADonut::get_model(&self.actor_base.borrow());
-        self.actor_base.borrow_mut().mesh_data.u_model_view_proj = model_view_projection(model,
-                                                                                         *camera,
-                                                                                         *projection);
-
-        window.encoder.draw(&self.actor_base.borrow().slice,
-                            &self.actor_base.borrow().pso,
-                            &self.actor_base.borrow().mesh_data);
+        Self::update_actor_base(&mut self.actor_base.borrow_mut());
     }
 }
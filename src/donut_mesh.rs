@@ -1,4 +1,4 @@
-use crate::{math, base_mesh_trait::{self, MeshFactory}};
+use crate::{math, base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
 
 
 extern crate piston_window;
@@ -26,8 +26,8 @@ impl DonutMeshFactory
     pub fn new(major_radius: f32, minor_radius: f32) -> Self
     {
         DonutMeshFactory {
-            major_radius: major_radius,
-            minor_radius: minor_radius,
+            major_radius,
+            minor_radius,
             segments_major: 32,
             segments_minor: 32,
         }
@@ -39,8 +39,11 @@ impl base_mesh_trait::IntoDesc for DonutMeshFactory
 {
     fn into_desc(&self) -> base_mesh_trait::MeshDesc 
     {
-        base_mesh_trait::MeshDesc { 
-            desc: ([ self.major_radius, self.minor_radius, 0., 0. ]),
+        base_mesh_trait::MeshDesc::Donut {
+            major_radius: self.major_radius,
+            minor_radius: self.minor_radius,
+            segments_major: self.segments_major,
+            segments_minor: self.segments_minor,
         }
     }
 }
@@ -48,10 +51,10 @@ impl base_mesh_trait::IntoDesc for DonutMeshFactory
 
 impl crate::base_mesh_trait::MeshFactory for DonutMeshFactory 
 {
-    fn create_mesh_instance_on_gpu(&self,
-                                   open_gl: &crate::piston_window::OpenGL,
-                                   factory: &mut gfx_device_gl::Factory) -> crate::base_mesh_trait::BasicMeshOnGpu
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
     {
+        self.into_desc().validate()?;
+
         let major_radius = self.major_radius;
         let minor_radius = self.minor_radius;
         let segments_major = self.segments_major;
@@ -87,19 +90,15 @@ impl crate::base_mesh_trait::MeshFactory for DonutMeshFactory
                 let next_i = (i + 1) % segments_major;
                 let next_j = (j + 1) % segments_minor;
 
-                let a = (i * segments_minor + j) as u16;
-                let b = (next_i * segments_minor + j) as u16;
-                let c = (next_i * segments_minor + next_j) as u16;
-                let d = (i * segments_minor + next_j) as u16;
+                let a = (i * segments_minor + j) as u32;
+                let b = (next_i * segments_minor + j) as u32;
+                let c = (next_i * segments_minor + next_j) as u32;
+                let d = (i * segments_minor + next_j) as u32;
 
                 index_data.extend_from_slice(&[a, b, c, c, d, a]);
             }
         }
     
-        base_mesh_trait::BasicMeshOnGpu::new_from_vertices(open_gl, 
-                                                      factory,
-                                                      base_mesh_trait::pipe::new(),
-                                                      &vertex_data,
-                                                      &index_data)
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
     }
 }
@@ -0,0 +1,56 @@
+use std::collections::VecDeque;
+
+use crate::{
+    rule_set_trait::{self, RuleSet},
+    solver::{self, AutoGameMove},
+    tower_state::{Peg, TowerState},
+};
+
+
+// Classic rules that also let disks of the same width sit on each other.
+pub struct EqualSizeRules;
+
+
+
+impl RuleSet for EqualSizeRules
+{
+    fn name(&self) -> &str
+    {
+        "equal-size"
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, true)
+    }
+
+
+    fn allows_equal_widths(&self) -> bool
+    {
+        true
+    }
+
+
+    // Disks of one width are interchangeable, moving a whole size group costs one move per disk
+    // and the widest group only has to travel once, which beats any order of distinct disks.
+    fn solve(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        let start = from.tower_peg()?;
+        let goal = to.tower_peg()?;
+        let mut moves = VecDeque::new();
+
+        if start != goal {
+            solver::generate_grouped_moves(&mut moves, &from.width_counts(), start, goal);
+        }
+
+        Some(moves)
+    }
+
+
+    // Distinct widths double the moves, repeated ones only add to them.
+    fn max_disks_amount(&self) -> i32
+    {
+        20
+    }
+}
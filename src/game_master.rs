@@ -1,23 +1,42 @@
 #![allow(dead_code)]
 
-use std::{collections::VecDeque, isize, usize};
+use std::collections::VecDeque;
+use std::rc::Rc;
 
 use piston::{Button, Key};
-use vecmath::Matrix4;
 
 use crate::{
-    actor_trait::{self, Actor},
+    actor_trait::{self, Actor, ActorBase},
     animator::Anmiator,
-    base_mesh_trait::IntoDesc,
+    arrow_actor::AArrow,
+    arrow_mesh::ArrowMeshFactory,
+    board_actor::ABoard,
+    box_mesh::BoxMeshFactory,
+    base_mesh_trait::{IntoDesc, MeshDesc},
+    classic_rules::ClassicRules,
     donut_actor::ADonut,
     donut_mesh::DonutMeshFactory,
+    game_settings::{DiskShape, GameSettings},
+    game_statistics::GameStatistics,
+    master_trait::Master,
+    mesh_cache::MeshCache,
+    mesh_error::MeshError,
+    mini_map::MiniMap,
+    quaternion::Quaternion,
+    renderer::Renderer,
+    rule_set_trait::RuleSet,
+    scene_graph::{self, SceneNode},
+    solver::AutoGameMove,
     stick_actor::AStick,
-    stick_mesh::StickMeshFactory
+    stick_mesh::StickMeshFactory,
+    tower_state::{Peg, TowerState, PEGS_AMOUNT},
+    washer_mesh::WasherMeshFactory,
 };
 
 
 type Stack<T> = Vec<T>;
 
+
 pub struct GameMaster
 {
     animator: Anmiator,
@@ -32,8 +51,26 @@ pub struct GameMaster
     button_choice_2: Option<Key>,
 
     dounuts_amount: i32,
+    disk_shape: DiskShape,
+    // How far apart stacked disks are, taken from the disk model when there is one.
+    donut_height: f32,
+
+    rules: Rc<dyn RuleSet>,
+    direction_arrows: Vec::<AArrow>,
+    board: Option<ABoard>,
+
+    // The sticks and the disks hang from their peg, the pegs and the arrows from the board.
+    board_node: SceneNode,
+    pegs: Vec<SceneNode>,
+
+    target_state: TowerState,
+    puzzle_mode: bool,
+    mini_map: MiniMap,
+    mini_map_donuts: Vec::<ADonut>,
 
     playing: bool,
+    statistics: GameStatistics,
+    shown_hint: Option<ShownHint>,
 
     auto_gamer_moves: VecDeque<AutoGameMove>,
     auto_gamer: bool,
@@ -43,9 +80,9 @@ pub struct GameMaster
 fn call_on_stack<F, ACTOR: actor_trait::Actor>(mut f: F, stack: &mut Stack<ACTOR>)
     where F: FnMut(&mut ACTOR)
 {
-    for mut actor in stack.iter_mut()
+    for actor in stack.iter_mut()
     {
-        f(&mut actor);
+        f(actor);
     }
 }
 
@@ -62,12 +99,37 @@ const POS_FAR_STICK: f32 = -12.;
 const POS_CLOSE_STICK: f32 = -10.;
 const DISTANCE_BETWEEN_STICKS: f32 = 15.;
 const DONUT_HEIGHT: f32 = 0.6;
+// The major radius of a disk grows by this much per width, the cross section stays the same.
+const DISK_RADIUS_STEP: f32 = 0.2;
+const DISK_TUBE_RADIUS: f32 = 0.35;
+const HINT_DONUT_COLOR: [f32; 4] = [ 1.0, 1.0, 1.0, 1.0 ];
+const HINT_STICK_COLOR: [f32; 4] = [ 1.0, 0.85, 0.1, 1.0 ];
+const ARROW_COLOR: [f32; 4] = [ 0.9, 0.15, 0.15, 1.0 ];
+const ARROW_HEIGHT: f32 = GROUND_OFFSET - 0.6;
+const ARROW_SIDE_OFFSET: f32 = 0.7;
+const BOARD_THICKNESS: f32 = 0.5;
+const BOARD_COLOR: [f32; 4] = [ 0.45, 0.3, 0.18, 1.0 ];
+// The auto gamer plans this many moves ahead, a big classic tower never fits in memory at once.
+const AUTO_GAMER_LOOKAHEAD: usize = 1 << 16;
+const MINI_MAP_OFFSET: [f32; 3] = [ 0., 11., POS_FAR_STICK ];
+// Where the sticks stand on their peg, the stick mesh is centred.
+const STICK_POSITION: [f32; 3] = [ 0., 1. - GROUND_OFFSET, 0. ];
+
+
+struct ShownHint
+{
+    game_move: AutoGameMove,
+    donut_color: [f32; 4],
+    stick_color: [f32; 4],
+}
 
 
-struct AutoGameMove 
+// The foot of the peg on the board, the middle one stands closer.
+fn peg_position(peg: Peg) -> vecmath::Vector3<f32>
 {
-    from: u32,
-    to: u32,
+    let z = if peg == 1 { POS_CLOSE_STICK - POS_FAR_STICK } else { 0. };
+
+    [ DISTANCE_BETWEEN_STICKS * peg as f32 - DISTANCE_BETWEEN_STICKS, 0., z ]
 }
 
 
@@ -89,9 +151,24 @@ impl GameMaster
             button_choice_2: None,
 
             dounuts_amount: -1,
+            disk_shape: DiskShape::Torus,
+            donut_height: DONUT_HEIGHT,
 
-            playing: true,
+            rules: Rc::new(ClassicRules),
+            direction_arrows: Vec::new(),
+            board: None,
+
+            board_node: scene_graph::group([ 0., GROUND_OFFSET, POS_FAR_STICK ]),
+            pegs: Vec::new(),
 
+            target_state: TowerState::empty(),
+            puzzle_mode: false,
+            mini_map: MiniMap::new(MINI_MAP_OFFSET),
+            mini_map_donuts: Vec::new(),
+
+            playing: true,
+            statistics: GameStatistics::new(),
+            shown_hint: None,
 
             auto_gamer_moves: VecDeque::new(),
             auto_gamer: false,
@@ -101,169 +178,291 @@ impl GameMaster
 
    
     pub fn initialize(&mut self,
-                      dounuts_amount: i32,
-                      open_gl:        &crate::piston_window::OpenGL, 
-                      window:         &piston_window::PistonWindow,
-                      factory:        &mut gfx_device_gl::Factory)
+                      settings: &GameSettings,
+                      renderer: &mut Renderer) -> Result<(), MeshError>
     {
-        self.dounuts_amount = dounuts_amount;
+        self.dounuts_amount = settings.dounuts_amount;
+        self.disk_shape = settings.disk_shape;
+        self.rules = settings.rules.clone();
+
+
+
+        let stick_factory = StickMeshFactory::new();
 
+        for peg in 0..PEGS_AMOUNT
+        {
+            let peg_node = scene_graph::group(peg_position(peg));
+            scene_graph::attach(&self.board_node, &peg_node);
 
+            let stick = AStick::initialize(stick_factory.into_desc(), renderer)?;
+            <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
+            <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(), STICK_POSITION);
+            scene_graph::attach(&peg_node, &stick.actor_base);
 
-        let stick_factory = StickMeshFactory {};
+            self.sticks.push(stick);
+            self.pegs.push(peg_node);
+        }
 
-        let stick = AStick::initialize(stick_factory.into_desc(), open_gl, window, factory);
-        <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
-        <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(), 
-                                        [ -DISTANCE_BETWEEN_STICKS, 1.0, POS_FAR_STICK ]);
 
-        self.sticks.push(stick);
+        let mut start_state = self.rules.start_state(settings);
 
+        if settings.scrambled_start {
+            start_state = start_state.scrambled();
+        }
 
-        let stick = AStick::initialize(stick_factory.into_desc(), open_gl, window, factory);
-        <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
-        <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(),
-                                        [ 0., 1., POS_CLOSE_STICK ]);
+        // Disk models stack by their own thickness instead of nesting into each other like the donuts.
+        if renderer.cache().has_model(self.donut_desc(1).kind()) {
+            self.donut_height = self.model_donut_height(&start_state, renderer.cache_mut())?;
+        }
 
-        self.sticks.push(stick);
+        if settings.board_model.is_some() {
+            self.generate_board(renderer)?;
+        }
 
-        let stick = AStick::initialize(stick_factory.into_desc(), open_gl, window, factory);
-        <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
-        <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(),
-                                        [ DISTANCE_BETWEEN_STICKS, 1.0, POS_FAR_STICK ]);
+        self.generate_donuts(&start_state, renderer)?; 
 
-        self.sticks.push(stick);
+        if let Some(directions) = self.rules.allowed_directions() {
+            self.generate_direction_arrows(&directions, renderer)?;
+        }
 
+        match settings.puzzle_distance
+        {
+            Some(distance) => self.generate_puzzle(&start_state, distance, settings.start_peg, renderer)?,
+            None => self.target_state = self.rules.goal_state(settings),
+        }
 
-        self.generate_donuts(open_gl, window, factory); 
+        Ok(())
     }
 
-    pub fn update(&mut self, button: Option<Button>) 
+
+
+    // Relative to the peg the donut is on.
+    fn donut_position(&self, level: usize) -> vecmath::Vector3<f32>
     {
-        if !self.playing {
-            return
-        }
+        [ 0., self.donut_height * level as f32, 0. ]
+    }
 
 
-        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.stack_one);
-        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.stack_two);
-        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.stack_three);
-        call_on_stack(| actor: &mut AStick | -> () { actor.update() }, &mut self.sticks);
-    
-        if self.animator.is_in_animation() {
-            self.animator.update();
-            return;
-        }
 
-        if self.check_win_condition() {
-            self.playing = false;
+    pub fn check_win_condition(&mut self) -> bool
+    {
+        self.rules.is_won(&self.current_state(), &self.target_state)
+    }
 
-            println!("You won!");
-        }
 
-        if self.auto_gamer {
-            self.auto_game();
-            return;
-        }
 
-        if let Some(Button::Keyboard(key)) = button
+    fn generate_donuts(&mut self, 
+                       start_state: &TowerState,
+                       renderer: &mut Renderer) -> Result<(), MeshError>
+    {
+        for (peg, disks) in start_state.pegs.iter().enumerate()
         {
-            match key 
+            for disk in disks
             {
-                Key::D1 | Key::D2 | Key::D3 => { self.start_donut_routine(key); },
-                Key::A => {
+                let mut donut = self.initialize_donut(disk.width, renderer)?;
+                let level = self.get_stack(peg as i32).len();
 
+                <ADonut as Actor>::rotate_x(&mut donut.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
+                <ADonut as Actor>::set_position(&mut donut.actor_base.borrow_mut(), self.donut_position(level));
+                scene_graph::attach(&self.pegs[peg], &donut.actor_base);
+                donut.donut_width = disk.width;
+                donut.donut_group = disk.group;
+                donut.north_up = disk.north_up;
 
+                if let Some(color) = self.rules.donut_color(disk) {
+                    donut.actor_base.borrow_mut().color = color;
+                }
 
-                    self.auto_gamer = true;
-                },
-                _ => ()
+                self.get_stack(peg as i32).push(donut);
             }
+        }
+
+        Ok(())
+    }
+
 
-            // println!("Pressed keyboard key '{:?}'", key);
-        };
 
+    // The thickest disk of the state, disks are built lying in the XY plane.
+    fn model_donut_height(&self, state: &TowerState, cache: &mut MeshCache) -> Result<f32, MeshError>
+    {
+        let mut height: f32 = 0.;
+
+        for disk in state.pegs.iter().flatten()
+        {
+            let (min, max) = cache.mesh_data(&self.donut_desc(disk.width))?.bounds();
+            height = height.max(max[2] - min[2]);
+        }
+
+        Ok(height)
     }
 
-    pub fn render(&mut self,
-                  mut window: &mut piston_window::PistonWindow,
-                  camera: &Matrix4<f32>,
-                  projection: vecmath::Matrix4<f32>)
+
+
+    // The board sits under the sticks and the direction arrows.
+    fn generate_board(&mut self,
+                      renderer: &mut Renderer) -> Result<(), MeshError>
     {
+        let board_factory = BoxMeshFactory::new(DISTANCE_BETWEEN_STICKS * 2. + 10., BOARD_THICKNESS, 14.);
+        let board = ABoard::initialize(board_factory.into_desc(), renderer)?;
 
-        call_on_stack(| actor: &mut ADonut | -> () { actor.render(&mut window, &camera, &projection) },
-                      &mut self.stack_one);
+        <ABoard as Actor>::set_position(&mut board.actor_base.borrow_mut(),
+                                        [ 0., ARROW_HEIGHT - GROUND_OFFSET - 0.05 - BOARD_THICKNESS * 0.5, 0. ]);
+        board.actor_base.borrow_mut().color = BOARD_COLOR;
+        scene_graph::attach(&self.board_node, &board.actor_base);
 
-        call_on_stack(| actor: &mut ADonut | -> () { actor.render(&mut window, &camera, &projection) },
-                      &mut self.stack_two);
+        self.board = Some(board);
+
+        Ok(())
+    }
 
-        call_on_stack(| actor: &mut ADonut | -> () { actor.render(&mut window, &camera, &projection) }, 
-                      &mut self.stack_three);
 
-        call_on_stack(| actor: &mut AStick | -> () { actor.render(&mut window, &camera, &projection) }, 
-                      &mut self.sticks);
+
+    fn donut_desc(&self, width: i32) -> MeshDesc
+    {
+        let major_radius = 0.40 + (width as f32 * DISK_RADIUS_STEP);
+
+        match self.disk_shape
+        {
+            DiskShape::Torus => DonutMeshFactory::new(major_radius, DISK_TUBE_RADIUS).into_desc(),
+            DiskShape::Washer => {
+                WasherMeshFactory::new(major_radius + DISK_TUBE_RADIUS, major_radius - DISK_TUBE_RADIUS, 0.55).into_desc()
+            },
+        }
     }
 
-    pub fn resize(&mut self, mut window: &mut piston_window::PistonWindow)
+
+
+    // Every disk shares the mesh of the narrowest one, so they are all drawn with one call.
+    // It is widened around its axis for the wider disks, a disk model is scaled up instead.
+    fn initialize_donut(&self, width: i32, renderer: &mut Renderer) -> Result<ADonut, MeshError>
     {
-        call_on_stack(| actor: &mut ADonut | -> () { actor.resize(&mut window) }, 
-                      &mut self.stack_one);
-        
-        call_on_stack(| actor: &mut ADonut | -> () { actor.resize(&mut window) }, 
-                      &mut self.stack_two);
+        let donut = ADonut::initialize(self.donut_desc(1), renderer)?;
+        let growth = (width - 1) as f32 * DISK_RADIUS_STEP;
 
-        call_on_stack(| actor: &mut ADonut | -> () { actor.resize(&mut window) }, 
-                      &mut self.stack_three);
+        if renderer.cache().has_model(self.donut_desc(1).kind()) {
+            // Models are fitted to the outer diameter of the disk they replace.
+            let narrowest = 0.40 + DISK_RADIUS_STEP + DISK_TUBE_RADIUS;
+            let scale = (narrowest + growth) / narrowest;
 
-        call_on_stack(| actor: &mut AStick | -> () { actor.resize(&mut window) }, 
-                      &mut self.sticks);
+            <ADonut as Actor>::set_scale(&mut donut.actor_base.borrow_mut(), [ scale, scale, scale ]);
+        } else {
+            donut.actor_base.borrow_mut().ring_growth = growth;
+        }
+
+        Ok(donut)
     }
 
- 
 
-    pub fn check_win_condition(&mut self) -> bool
+
+    fn generate_direction_arrows(&mut self,
+                                 directions: &[AutoGameMove],
+                                 renderer: &mut Renderer) -> Result<(), MeshError>
     {
-        let third_stack = self.get_stack(2);
-    
+        for &AutoGameMove { from, to } in directions
+        {
+            let mut start = peg_position(from);
+            let mut end = peg_position(to);
+
+            // Arrows between the outer sticks go behind the middle stick instead of through it.
+            if from.abs_diff(to) == 2 {
+                start[2] -= 5.;
+                end[2] -= 5.;
+            }
+
+            let dx = end[0] - start[0];
+            let dz = end[2] - start[2];
+            let distance = (dx * dx + dz * dz).sqrt();
+
+            // Every arrow keeps to its right, so opposite directions don't cover each other.
+            start[0] -= dz / distance * ARROW_SIDE_OFFSET;
+            start[2] += dx / distance * ARROW_SIDE_OFFSET;
 
-        if third_stack.len() >= self.dounuts_amount as usize {
-            return true
+            let arrow_factory = ArrowMeshFactory::new(distance * 0.4, 0.8);
+            let arrow = AArrow::initialize(arrow_factory.into_desc(), renderer)?;
+
+            <AArrow as Actor>::rotate_y(&mut arrow.actor_base.borrow_mut(), (-dx).atan2(dz));
+            <AArrow as Actor>::set_position(&mut arrow.actor_base.borrow_mut(),
+                                            [ start[0] + dx * 0.3, ARROW_HEIGHT - GROUND_OFFSET, start[2] + dz * 0.3 ]);
+            arrow.actor_base.borrow_mut().color = ARROW_COLOR;
+            scene_graph::attach(&self.board_node, &arrow.actor_base);
+
+            self.direction_arrows.push(arrow);
         }
 
-        false
+        Ok(())
     }
 
 
 
-    fn generate_donuts(&mut self, 
-                       open_gl: &crate::piston_window::OpenGL, 
-                       window:  &piston_window::PistonWindow,
-                       factory: &mut gfx_device_gl::Factory)
+    fn generate_puzzle(&mut self,
+                       start_state: &TowerState,
+                       distance:    u64,
+                       start_peg:   Peg,
+                       renderer: &mut Renderer) -> Result<(), MeshError>
     {
-        let max_i = self.dounuts_amount;
+        self.target_state = self.rules.random_target(start_state, start_peg, distance);
+        self.puzzle_mode = true;
 
-        for i in 0..max_i
+        match self.rules.distance(start_state, &self.target_state)
         {
-            let i_diff = max_i - i;
+            Some(distance) => println!("Reach this configuration in {} moves:\n{}", distance, self.target_state),
+            None => println!("Reach this configuration:\n{}", self.target_state),
+        }
 
-            let donut_factory = DonutMeshFactory {
-                major_radius: 0.40 + (i_diff as f32 * 0.2),
-                minor_radius: 0.35, 
-                segments_major: 28,
-                segments_minor: 16,
-            };
+        self.generate_mini_map(renderer)?;
+
+        Ok(())
+    }
 
 
-            let mut donut = ADonut::initialize(donut_factory.into_desc(), open_gl, window, factory);
-            let offset = DONUT_HEIGHT * self.stack_one.len() as f32 + GROUND_OFFSET;
 
-            <ADonut as Actor>::rotate_x(&mut donut.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
-            <ADonut as Actor>::set_position(&mut donut.actor_base.borrow_mut(), 
-                                            [ -DISTANCE_BETWEEN_STICKS, offset, POS_FAR_STICK ]);
-            donut.donut_width = (max_i - i) as i32;
+    fn generate_mini_map(&mut self,
+                         renderer: &mut Renderer) -> Result<(), MeshError>
+    {
+        let stick_factory = StickMeshFactory::new();
 
-            self.stack_one.push(donut);
+        for peg in 0..PEGS_AMOUNT
+        {
+            let stick = AStick::initialize(stick_factory.into_desc(), renderer)?;
+
+            <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
+            <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(), STICK_POSITION);
+
+            self.mini_map.add_peg(peg_position(peg)[0], stick);
         }
+
+        let target_state = self.target_state.clone();
+
+        for (peg, disks) in target_state.pegs.iter().enumerate()
+        {
+            for (level, disk) in disks.iter().enumerate()
+            {
+                let original = self.all_donuts()
+                                   .find(|donut| donut.donut_width == disk.width && donut.donut_group == disk.group)
+                                   .unwrap();
+                let color = self.rules.donut_color(disk).unwrap_or(original.actor_base.borrow().color);
+
+                let mut donut = self.initialize_donut(disk.width, renderer)?;
+
+                <ADonut as Actor>::rotate_x(&mut donut.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
+                donut.actor_base.borrow_mut().color = color;
+                self.mini_map.place(peg, &donut.actor_base, self.donut_position(level));
+                donut.donut_width = disk.width;
+                donut.donut_group = disk.group;
+                donut.north_up = disk.north_up;
+
+                self.mini_map_donuts.push(donut);
+            }
+        }
+
+        Ok(())
+    }
+
+
+
+    fn all_donuts(&self) -> impl Iterator<Item = &ADonut>
+    {
+        self.stack_one.iter().chain(self.stack_two.iter()).chain(self.stack_three.iter())
     }
 
 
@@ -279,20 +478,9 @@ impl GameMaster
     fn check_if_move_possible(&mut self) -> bool
     {
         let index_a = convert_key_to_i32(self.button_choice_1.unwrap());
-        if self.get_stack(index_a).is_empty() {
-            return false
-        }
-
         let index_b = convert_key_to_i32(self.button_choice_2.unwrap());
-        if self.get_stack(index_b).is_empty() {
-            return true
-        }
-
-        if self.get_stack(index_a).last().unwrap().donut_width > self.get_stack(index_b).last().unwrap().donut_width {
-            return false
-        }
 
-        true
+        self.rules.is_move_legal(&self.current_state(), index_a as Peg, index_b as Peg)
     }
 
 
@@ -314,24 +502,37 @@ impl GameMaster
     {
         let index_a = convert_key_to_i32(self.button_choice_1.unwrap());
         let index_b = convert_key_to_i32(self.button_choice_2.unwrap());
-        let distance = DISTANCE_BETWEEN_STICKS * index_b as f32 - DISTANCE_BETWEEN_STICKS;
 
-        let donut = self.get_stack(index_a).pop().unwrap();
+        self.clear_hint();
+        self.statistics.moves += 1;
+
+        // The rules may change the moved donut, e.g. magnets flip over on the way.
+        let mut state = self.current_state();
+        self.rules.apply_move(&mut state, index_a as Peg, index_b as Peg);
+
+        let moved = *state.top(index_b as Peg).unwrap();
+        let mut donut = self.get_stack(index_a).pop().unwrap();
         let stack_len = self.get_stack(index_b).len();
-        let mut z = POS_FAR_STICK;
+        let flip = moved.north_up != donut.north_up;
+
+        donut.north_up = moved.north_up;
 
-        if index_b == 1 {
-            z = POS_CLOSE_STICK;
+        if let Some(color) = self.rules.donut_color(&moved) {
+            donut.actor_base.borrow_mut().color = color;
         }
 
-        let starting_pos = <ADonut as Actor>::get_pos(&donut.actor_base.borrow());
-    
-        
-        self.animator.queue_animation(donut.actor_base.clone(), 
-                                      starting_pos,
-                                      [ distance,                                          
-                                        DONUT_HEIGHT * stack_len as f32 + GROUND_OFFSET, 
-                                        z ]);
+        // The donut flies over from where it is on the old peg.
+        scene_graph::reparent(&self.pegs[index_b as usize], &donut.actor_base);
+
+        let mut target = <ADonut as Actor>::get_transform(&donut.actor_base.borrow());
+        target.translation = self.donut_position(stack_len);
+
+        // Upside down around its own X axis, the same way `rotate_x` turns.
+        if flip {
+            target.rotation = target.rotation * Quaternion::from_axis_angle([ 1., 0., 0. ], -std::f32::consts::PI);
+        }
+
+        self.animator.queue_animation(donut.actor_base.clone(), target);
 
 
         self.get_stack(index_b).push(donut);
@@ -357,6 +558,7 @@ impl GameMaster
 
         if !self.check_if_move_possible() {
             self.flush_choices();
+            self.statistics.invalid_moves += 1;
             println!("The move is invalid."); 
             return;
         }
@@ -367,9 +569,56 @@ impl GameMaster
 
 
 
+    fn show_hint(&mut self)
+    {
+        self.clear_hint();
+
+        let Some(mut moves) = self.rules.next_moves(&self.current_state(), &self.target_state, 1) else {
+            println!("There are too many donuts to find a hint.");
+            return;
+        };
+
+        let Some(game_move) = moves.pop_front() else {
+            return;
+        };
+
+        self.statistics.hints += 1;
+        println!("Hint: move the donut from {} to {}.", game_move.from + 1, game_move.to + 1);
+
+        let donut = self.get_stack(game_move.from as i32).last().unwrap().actor_base.clone();
+        let stick = self.sticks[game_move.to].actor_base.clone();
+
+        let donut_color = std::mem::replace(&mut donut.borrow_mut().color, HINT_DONUT_COLOR);
+        let stick_color = std::mem::replace(&mut stick.borrow_mut().color, HINT_STICK_COLOR);
+
+        self.shown_hint = Some(ShownHint { game_move, donut_color, stick_color });
+    }
+
+
+
+    fn clear_hint(&mut self)
+    {
+        let Some(hint) = self.shown_hint.take() else {
+            return;
+        };
+
+        // The hinted donut is still on top, every move clears the hint before it happens.
+        if let Some(donut) = self.get_stack(hint.game_move.from as i32).last() {
+            donut.actor_base.borrow_mut().color = hint.donut_color;
+        }
+
+        self.sticks[hint.game_move.to].actor_base.borrow_mut().color = hint.stick_color;
+    }
+
+
+
     fn generate_auto_gamer_moves(&mut self)
     {
-        generate_moves(&mut self.auto_gamer_moves, self.dounuts_amount, 0, 2, 1);
+        match self.rules.next_moves(&self.current_state(), &self.target_state, AUTO_GAMER_LOOKAHEAD)
+        {
+            Some(moves) => self.auto_gamer_moves = moves,
+            None => println!("There are too many donuts for the auto gamer."),
+        }
     }
 
 
@@ -380,19 +629,105 @@ impl GameMaster
             self.generate_auto_gamer_moves();
         }
 
-        let game_move = self.auto_gamer_moves.pop_front().unwrap();
-        self.button_choice_1 = Some(Key::from('1' as u32 + game_move.from));
-        self.start_donut_routine(Key::from('1' as u32 + game_move.to));
+        let Some(game_move) = self.auto_gamer_moves.pop_front() else {
+            self.auto_gamer = false;
+            return;
+        };
+
+        self.button_choice_1 = Some(Key::from('1' as u32 + game_move.from as u32));
+        self.start_donut_routine(Key::from('1' as u32 + game_move.to as u32));
     }
 }
 
-fn generate_moves(target: &mut VecDeque<AutoGameMove>, n: i32, from: u32, to: u32, aux: u32)
+
+
+impl Master for GameMaster
 {
-    if n == 0 {
-        return;
+    fn update(&mut self, button: Option<Button>) 
+    {
+        if !self.playing {
+            return
+        }
+
+
+        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.stack_one);
+        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.stack_two);
+        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.stack_three);
+        call_on_stack(| actor: &mut AStick | -> () { actor.update() }, &mut self.sticks);
+        call_on_stack(| actor: &mut AArrow | -> () { actor.update() }, &mut self.direction_arrows);
+        self.board.iter_mut().for_each(|actor| actor.update());
+        self.mini_map.update();
+        call_on_stack(| actor: &mut ADonut | -> () { actor.update() }, &mut self.mini_map_donuts);
+    
+        if self.animator.is_in_animation() {
+            self.animator.update();
+            return;
+        }
+
+        if self.check_win_condition() {
+            self.playing = false;
+
+            println!("You won! {}", self.statistics);
+            return;
+        }
+
+        if self.auto_gamer {
+            self.auto_game();
+            return;
+        }
+
+        if let Some(Button::Keyboard(key)) = button
+        {
+            match key 
+            {
+                Key::D1 | Key::D2 | Key::D3 => { self.start_donut_routine(key); },
+                Key::H => { self.show_hint(); },
+                Key::A => {
+                    self.clear_hint();
+                    self.auto_gamer = true;
+                },
+                _ => ()
+            }
+
+            // println!("Pressed keyboard key '{:?}'", key);
+        };
+
     }
 
-    generate_moves(target, n - 1, from, aux, to);
-    target.push_back(AutoGameMove { from: (from), to: (to) });
-    generate_moves(target, n - 1, aux, to, from);
+    fn visit_actors(&self, visit: &mut dyn FnMut(&ActorBase))
+    {
+        self.all_donuts().for_each(|actor| visit(&actor.actor_base.borrow()));
+        self.sticks.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+        self.direction_arrows.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+        self.board.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+        self.mini_map.visit_actors(visit);
+        self.mini_map_donuts.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+    }
+
+    fn current_state(&self) -> TowerState
+    {
+        let mut state = TowerState::empty();
+
+        for (peg, stack) in [ &self.stack_one, &self.stack_two, &self.stack_three ].iter().enumerate()
+        {
+            state.pegs[peg] = stack.iter().map(ADonut::as_disk).collect();
+        }
+
+        state
+    }
+
+    fn is_busy(&self) -> bool
+    {
+        self.playing && (self.animator.is_in_animation() || self.auto_gamer)
+    }
+
+    fn is_playing(&self) -> bool
+    {
+        self.playing
+    }
+
+    fn statistics(&self) -> &GameStatistics
+    {
+        &self.statistics
+    }
 }
@@ -0,0 +1,264 @@
+#![allow(dead_code)]
+
+use std::rc::Rc;
+
+use crate::{
+    classic_rules::ClassicRules,
+    equal_size_rules::EqualSizeRules,
+    rule_set_registry::RuleSetRegistry,
+    rule_set_trait::RuleSet,
+    tower_state::{Peg, TowerState, PEGS_AMOUNT},
+};
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameMode
+{
+    Hanoi,
+    // Tower of London planning test, a series of timed trials with a move budget.
+    London,
+}
+
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiskShape
+{
+    Torus,
+    // Flat ring with bevelled edges.
+    Washer,
+}
+
+
+
+pub struct GameSettings
+{
+    pub mode: GameMode,
+
+    pub dounuts_amount: i32,
+
+    // Widths of the starting tower when several disks share a size, `None` is one disk per width.
+    pub disk_sizes: Option<Vec<i32>>,
+    pub disk_shape: DiskShape,
+
+    // OBJ, glTF or GLB files replacing the procedural disks and sticks, a board is only added with a model.
+    pub disk_model: Option<String>,
+    pub peg_model: Option<String>,
+    pub board_model: Option<String>,
+
+    // Some(distance) turns on the "reach this configuration" puzzle mode,
+    // the target is generated that many optimal moves away from the start.
+    pub puzzle_distance: Option<u64>,
+
+    pub start_peg: Peg,
+    pub goal_peg: Peg,
+
+    // Every ring is dropped on a random stick, any such layout is legal
+    // as long as the rings keep their size order.
+    pub scrambled_start: bool,
+
+    pub rules: Rc<dyn RuleSet>,
+
+    // Tower of London session: the amount of trials, how many moves above the optimal
+    // solution the budget allows and the CSV file every trial is appended to.
+    pub trials: u32,
+    pub budget_slack: u32,
+    pub trial_log: String,
+}
+
+
+
+impl GameSettings
+{
+    pub fn new() -> Self
+    {
+        GameSettings {
+            mode: GameMode::Hanoi,
+            dounuts_amount: 5,
+            disk_sizes: None,
+            disk_shape: DiskShape::Torus,
+            disk_model: None,
+            peg_model: None,
+            board_model: None,
+            puzzle_distance: None,
+            start_peg: 0,
+            goal_peg: 2,
+            scrambled_start: false,
+            rules: Rc::new(ClassicRules),
+            trials: 10,
+            budget_slack: 0,
+            trial_log: "london_trials.csv".to_string(),
+        }
+    }
+
+
+
+    pub fn from_args<I: Iterator<Item = String>>(args: I) -> Self
+    {
+        let mut settings = GameSettings::new();
+        let registry = RuleSetRegistry::with_builtin();
+        let mut args = args.skip(1);
+        let mut disks_amount = None;
+
+        while let Some(arg) = args.next()
+        {
+            match arg.as_str()
+            {
+                "--mode" => match args.next().as_deref() {
+                    Some("hanoi") => settings.mode = GameMode::Hanoi,
+                    Some("london") => settings.mode = GameMode::London,
+                    _ => println!("--mode expects hanoi or london."),
+                },
+                "--trials" => match parse_value::<u32>(args.next()) {
+                    Some(trials) if trials > 0 => settings.trials = trials,
+                    _ => println!("--trials expects a positive number."),
+                },
+                "--slack" => match parse_value::<u32>(args.next()) {
+                    Some(slack) => settings.budget_slack = slack,
+                    None => println!("--slack expects the amount of moves allowed above the optimal solution."),
+                },
+                "--log" => match args.next() {
+                    Some(path) => settings.trial_log = path,
+                    None => println!("--log expects a file path."),
+                },
+                "--disks" => match parse_value::<i32>(args.next()) {
+                    Some(amount) if amount > 0 => disks_amount = Some(amount),
+                    _ => println!("--disks expects a positive number."),
+                },
+                "--sizes" => match parse_sizes(args.next()) {
+                    Some(sizes) => settings.disk_sizes = Some(sizes),
+                    None => println!("--sizes expects positive widths separated by commas, like 1,1,2,3."),
+                },
+                "--disk-shape" => match args.next().as_deref() {
+                    Some("torus") => settings.disk_shape = DiskShape::Torus,
+                    Some("washer") => settings.disk_shape = DiskShape::Washer,
+                    _ => println!("--disk-shape expects torus or washer."),
+                },
+                "--disk-model" | "--peg-model" | "--board-model" => match args.next() {
+                    Some(path) if arg == "--disk-model" => settings.disk_model = Some(path),
+                    Some(path) if arg == "--peg-model" => settings.peg_model = Some(path),
+                    Some(path) => settings.board_model = Some(path),
+                    None => println!("{} expects an OBJ, glTF or GLB file.", arg),
+                },
+                "--puzzle" => match parse_value::<u64>(args.next()) {
+                    Some(distance) if distance > 0 => settings.puzzle_distance = Some(distance),
+                    _ => println!("--puzzle expects a positive distance in moves."),
+                },
+                "--start" => match parse_peg(args.next()) {
+                    Some(peg) => settings.start_peg = peg,
+                    None => println!("--start expects a stick number from 1 to {}.", PEGS_AMOUNT),
+                },
+                "--goal" => match parse_peg(args.next()) {
+                    Some(peg) => settings.goal_peg = peg,
+                    None => println!("--goal expects a stick number from 1 to {}.", PEGS_AMOUNT),
+                },
+                "--scramble" => settings.scrambled_start = true,
+                "--rules" | "--variant" => match args.next().and_then(|name| registry.get(&name)) {
+                    Some(rules) => settings.rules = rules,
+                    None => println!("{} expects one of: {}, or custom:<moves> like custom:12,23,31.",
+                                     arg, registry.names().join(", ")),
+                },
+                _ => println!("Ignoring unknown argument {:?}", arg),
+            }
+        }
+
+        if settings.disk_sizes.is_some() && !settings.rules.uses_disk_sizes() {
+            println!("The {} rules build their own tower, ignoring --sizes.", settings.rules.name());
+            settings.disk_sizes = None;
+        }
+
+        // The sizes list every disk, so they decide how many there are.
+        match (&settings.disk_sizes, disks_amount)
+        {
+            (Some(sizes), Some(amount)) if amount != sizes.len() as i32 => {
+                println!("--sizes lists {} disks, ignoring --disks {}.", sizes.len(), amount);
+                settings.dounuts_amount = sizes.len() as i32;
+            },
+            (Some(sizes), _) => settings.dounuts_amount = sizes.len() as i32,
+            (None, Some(amount)) => settings.dounuts_amount = amount,
+            (None, None) => (),
+        }
+
+        if settings.has_equal_sizes() && !settings.rules.allows_equal_widths() {
+            println!("The {} rules don't allow disks of the same size, playing the equal-size rules.", settings.rules.name());
+            settings.rules = Rc::new(EqualSizeRules);
+        }
+
+        let max_disks = settings.rules.max_disks_amount();
+
+        if settings.dounuts_amount > max_disks {
+            println!("The {} rules are played with at most {} disks, ignoring the {} above.",
+                     settings.rules.name(), max_disks, settings.dounuts_amount - max_disks);
+            settings.dounuts_amount = max_disks;
+
+            // The packed widths stay 1, 2, 3... when only the narrowest disks are kept.
+            if let Some(sizes) = &mut settings.disk_sizes {
+                sizes.sort();
+                sizes.truncate(max_disks as usize);
+            }
+        }
+
+        let won_right_away = settings.rules.start_state(&settings) == settings.rules.goal_state(&settings);
+
+        if won_right_away && !settings.scrambled_start && settings.mode == GameMode::Hanoi {
+            println!("The start and the goal stick are the same, the game is won right away.");
+        }
+
+        settings
+    }
+
+
+
+    /// Full tower of the configured disks on `peg`.
+    pub fn tower(&self, peg: Peg) -> TowerState
+    {
+        match &self.disk_sizes
+        {
+            Some(sizes) => TowerState::from_sizes(sizes, peg),
+            None => TowerState::tower(self.dounuts_amount, peg),
+        }
+    }
+
+
+
+    pub fn has_equal_sizes(&self) -> bool
+    {
+        self.disk_sizes.as_ref().is_some_and(|sizes| self.tower(0).width_counts().len() < sizes.len())
+    }
+}
+
+
+
+fn parse_value<T: std::str::FromStr>(value: Option<String>) -> Option<T>
+{
+    value?.parse().ok()
+}
+
+
+
+// The widths are packed into 1, 2, 3... keeping their order and repeats,
+// the solvers index disks by width.
+fn parse_sizes(value: Option<String>) -> Option<Vec<i32>>
+{
+    let sizes: Vec<i32> = value?.split(',').map(|size| size.trim().parse().ok()).collect::<Option<_>>()?;
+
+    if sizes.iter().any(|size| *size <= 0) {
+        return None
+    }
+
+    let mut distinct = sizes.clone();
+    distinct.sort();
+    distinct.dedup();
+
+    Some(sizes.iter().map(|size| distinct.binary_search(size).unwrap() as i32 + 1).collect())
+}
+
+
+
+fn parse_peg(value: Option<String>) -> Option<Peg>
+{
+    match parse_value::<Peg>(value) {
+        Some(peg) if (1..=PEGS_AMOUNT).contains(&peg) => Some(peg - 1),
+        _ => None,
+    }
+}
@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+
+pub struct GameStatistics
+{
+    pub moves: u32,
+    pub invalid_moves: u32,
+    pub hints: u32,
+
+    started: Instant,
+}
+
+
+
+impl GameStatistics
+{
+    pub fn new() -> Self
+    {
+        GameStatistics {
+            moves: 0,
+            invalid_moves: 0,
+            hints: 0,
+            started: Instant::now(),
+        }
+    }
+
+
+
+    pub fn elapsed(&self) -> Duration
+    {
+        self.started.elapsed()
+    }
+}
+
+
+
+impl std::fmt::Display for GameStatistics
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f,
+               "moves: {}, invalid moves: {}, hints: {}, time: {:.1}s",
+               self.moves,
+               self.invalid_moves,
+               self.hints,
+               self.elapsed().as_secs_f32())
+    }
+}
@@ -0,0 +1,172 @@
+#![allow(dead_code)]
+
+extern crate gfx;
+
+use std::collections::HashMap;
+
+use gfx::{
+    Factory,
+    traits::FactoryExt,
+};
+use opengl_graphics::GLSL;
+use piston_window::Window;
+use shader_version::Shaders;
+use vecmath::Matrix4;
+
+use crate::{
+    base_mesh_trait::{Instance, MeshData, Vertex},
+    mesh_cache::{MeshCache, MeshHandle},
+    render_backend_trait::{DrawCall, RenderBackend},
+};
+
+
+type Resources = gfx_device_gl::Resources;
+
+gfx_pipeline!(
+    instanced_pipe
+    {
+        vbuf:        gfx::VertexBuffer<Vertex>                     = (),
+        instances:   gfx::InstanceBuffer<Instance>                 = (),
+        u_view_proj: gfx::Global<[[f32; 4]; 4]>                    = "u_view_proj",
+        out_color:   gfx::RenderTarget<::gfx::format::Srgba8>      = "o_Color",
+        out_depth:   gfx::DepthTarget<::gfx::format::DepthStencil> = gfx::preset::depth::LESS_EQUAL_WRITE,
+    }
+);
+
+
+
+// Meshes whose vertices can all be addressed with 16 bits get the smaller index buffer.
+fn upload_mesh(factory: &mut gfx_device_gl::Factory,
+               mesh:    &MeshData) -> (gfx::handle::Buffer<Resources, Vertex>, gfx::Slice<Resources>)
+{
+    if mesh.vertices.len() <= u16::MAX as usize + 1 {
+        let indices: Vec<u16> = mesh.indices.iter().map(|index| *index as u16).collect();
+        factory.create_vertex_buffer_with_slice(&mesh.vertices, indices.as_slice())
+    } else {
+        factory.create_vertex_buffer_with_slice(&mesh.vertices, mesh.indices.as_slice())
+    }
+}
+
+
+
+fn create_instanced_pso(open_gl: &piston_window::OpenGL,
+                        factory: &mut gfx_device_gl::Factory) -> gfx::PipelineState<Resources, instanced_pipe::Meta>
+{
+    let glsl = open_gl.to_glsl();
+    factory.create_pipeline_simple(Shaders::new()
+                                            .set(GLSL::V1_50, include_str!("../assets/shader_150_instanced.vert"))
+                                            .get(glsl).unwrap().as_bytes(),
+                                   Shaders::new()
+                                            .set(GLSL::V1_50, include_str!("../assets/shader_150_instanced.frag"))
+                                            .get(glsl).unwrap().as_bytes(),
+                                   instanced_pipe::new()).unwrap()
+}
+
+
+
+// A mesh uploaded the first time it's drawn.
+struct GpuMesh
+{
+    vbuf: gfx::handle::Buffer<Resources, Vertex>,
+    slice: gfx::Slice<Resources>,
+    // Kept between frames and only recreated when the mesh has more instances than it fits.
+    instances: Option<gfx::handle::Buffer<Resources, Instance>>,
+}
+
+
+
+/// The OpenGL resources of the game window: the instanced pipeline and every mesh drawn so far.
+pub struct GlBackend
+{
+    factory: gfx_device_gl::Factory,
+    pso: gfx::PipelineState<Resources, instanced_pipe::Meta>,
+    meshes: HashMap<MeshHandle, GpuMesh>,
+}
+
+
+
+/// The backend bound to the window for the frame being drawn.
+pub struct GlFrame<'a>
+{
+    backend: &'a mut GlBackend,
+    window: &'a mut piston_window::PistonWindow,
+}
+
+
+
+impl GlBackend
+{
+    pub fn new(open_gl: &piston_window::OpenGL, factory: &gfx_device_gl::Factory) -> Self
+    {
+        let mut factory = factory.clone();
+        let pso = create_instanced_pso(open_gl, &mut factory);
+
+        GlBackend {
+            factory,
+            pso,
+            meshes: HashMap::new(),
+        }
+    }
+
+
+
+    pub fn frame<'a>(&'a mut self, window: &'a mut piston_window::PistonWindow) -> GlFrame<'a>
+    {
+        GlFrame { backend: self, window }
+    }
+}
+
+
+
+impl RenderBackend for GlFrame<'_>
+{
+    fn viewport(&self) -> [u32; 2]
+    {
+        let size = self.window.window.draw_size();
+
+        [ size.width as u32, size.height as u32 ]
+    }
+
+
+
+    fn draw(&mut self, view_proj: &Matrix4<f32>, calls: &[DrawCall], meshes: &MeshCache)
+    {
+        let backend = &mut *self.backend;
+        let window = &mut *self.window;
+
+        for call in calls
+        {
+            let factory = &mut backend.factory;
+            let mesh = backend.meshes.entry(call.mesh).or_insert_with(|| {
+                let (vbuf, slice) = upload_mesh(factory, &meshes.get(call.mesh).data);
+
+                GpuMesh { vbuf, slice, instances: None }
+            });
+            let amount = call.instances.len();
+
+            if mesh.instances.as_ref().is_none_or(|buffer| buffer.len() < amount) {
+                mesh.instances = Some(factory.create_buffer(amount.next_power_of_two(),
+                                                            gfx::buffer::Role::Vertex,
+                                                            gfx::memory::Usage::Dynamic,
+                                                            gfx::memory::Bind::empty())
+                                             .unwrap());
+            }
+
+            let buffer = mesh.instances.clone().unwrap();
+            window.encoder.update_buffer(&buffer, &call.instances, 0).unwrap();
+
+            let mut slice = mesh.slice.clone();
+            slice.instances = Some((amount as u32, 0));
+
+            let data = instanced_pipe::Data {
+                vbuf: mesh.vbuf.clone(),
+                instances: buffer,
+                u_view_proj: *view_proj,
+                out_color: window.output_color.clone(),
+                out_depth: window.output_stencil.clone(),
+            };
+
+            window.encoder.draw(&slice, &backend.pso, &data);
+        }
+    }
+}
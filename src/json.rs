@@ -0,0 +1,299 @@
+#![allow(dead_code)]
+
+
+// Arrays and objects nested deeper are refused instead of running out of stack.
+const MAX_DEPTH: u32 = 128;
+
+
+/// Just enough JSON for reading glTF files.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Json
+{
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+
+
+impl Json
+{
+    pub fn parse(text: &str) -> Result<Json, String>
+    {
+        let mut parser = Parser { bytes: text.as_bytes(), at: 0, depth: 0 };
+        let value = parser.value()?;
+
+        parser.skip_whitespace();
+
+        match parser.at == parser.bytes.len() {
+            true => Ok(value),
+            false => Err(format!("unexpected text at byte {}", parser.at)),
+        }
+    }
+
+
+
+    /// Member of an object, `None` for missing members and other values.
+    pub fn get(&self, name: &str) -> Option<&Json>
+    {
+        match self {
+            Json::Object(members) => members.iter().find(|(key, _)| key == name).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+
+
+    pub fn at(&self, index: usize) -> Option<&Json>
+    {
+        self.as_array()?.get(index)
+    }
+
+
+
+    pub fn as_array(&self) -> Option<&[Json]>
+    {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+
+
+    pub fn as_f64(&self) -> Option<f64>
+    {
+        match self {
+            Json::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+
+
+    pub fn as_usize(&self) -> Option<usize>
+    {
+        self.as_f64().filter(|value| *value >= 0. && value.fract() == 0.).map(|value| value as usize)
+    }
+
+
+
+    pub fn as_str(&self) -> Option<&str>
+    {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+
+
+struct Parser<'a>
+{
+    bytes: &'a [u8],
+    at: usize,
+    // Arrays and objects around the value being read.
+    depth: u32,
+}
+
+
+
+impl Parser<'_>
+{
+    fn skip_whitespace(&mut self)
+    {
+        while self.bytes.get(self.at).is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.at += 1;
+        }
+    }
+
+
+
+    fn expect(&mut self, byte: u8) -> Result<(), String>
+    {
+        self.skip_whitespace();
+
+        match self.bytes.get(self.at) {
+            Some(found) if *found == byte => {
+                self.at += 1;
+                Ok(())
+            },
+            _ => Err(format!("expected '{}' at byte {}", byte as char, self.at)),
+        }
+    }
+
+
+
+    fn value(&mut self) -> Result<Json, String>
+    {
+        self.skip_whitespace();
+
+        match self.bytes.get(self.at)
+        {
+            Some(b'{' | b'[') if self.depth == MAX_DEPTH => Err(format!("nested too deep at byte {}", self.at)),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(format!("unexpected character at byte {}", self.at)),
+            None => Err("unexpected end of the text".to_string()),
+        }
+    }
+
+
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, String>) -> Result<Json, String>
+    {
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+
+        value
+    }
+
+
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, String>
+    {
+        match self.bytes[self.at..].starts_with(word.as_bytes()) {
+            true => {
+                self.at += word.len();
+                Ok(value)
+            },
+            false => Err(format!("unknown value at byte {}", self.at)),
+        }
+    }
+
+
+
+    fn number(&mut self) -> Result<Json, String>
+    {
+        let start = self.at;
+
+        while self.bytes.get(self.at).is_some_and(|byte| matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) {
+            self.at += 1;
+        }
+
+        std::str::from_utf8(&self.bytes[start..self.at])
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .map(Json::Number)
+            .ok_or_else(|| format!("invalid number at byte {}", start))
+    }
+
+
+
+    fn string(&mut self) -> Result<String, String>
+    {
+        self.expect(b'"')?;
+        let mut text = Vec::new();
+
+        loop
+        {
+            let Some(&byte) = self.bytes.get(self.at) else {
+                return Err("unterminated string".to_string());
+            };
+            self.at += 1;
+
+            match byte
+            {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = self.bytes.get(self.at).copied().ok_or("unterminated string")?;
+                    self.at += 1;
+
+                    match escaped
+                    {
+                        b'n' => text.push(b'\n'),
+                        b't' => text.push(b'\t'),
+                        b'r' => text.push(b'\r'),
+                        b'b' => text.push(8),
+                        b'f' => text.push(12),
+                        b'u' => {
+                            let code = self.bytes.get(self.at..self.at + 4)
+                                                 .and_then(|hex| std::str::from_utf8(hex).ok())
+                                                 .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                                 .ok_or_else(|| format!("invalid escape at byte {}", self.at))?;
+                            self.at += 4;
+
+                            let character = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                            text.extend_from_slice(character.to_string().as_bytes());
+                        },
+                        other => text.push(other),
+                    }
+                },
+                other => text.push(other),
+            }
+        }
+
+        String::from_utf8(text).map_err(|_| "a string isn't valid UTF-8".to_string())
+    }
+
+
+
+    fn array(&mut self) -> Result<Json, String>
+    {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.bytes.get(self.at) == Some(&b']') {
+            self.at += 1;
+            return Ok(Json::Array(values));
+        }
+
+        loop
+        {
+            values.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.bytes.get(self.at) {
+                Some(b',') => self.at += 1,
+                Some(b']') => {
+                    self.at += 1;
+                    return Ok(Json::Array(values));
+                },
+                _ => return Err(format!("expected ',' or ']' at byte {}", self.at)),
+            }
+        }
+    }
+
+
+
+    fn object(&mut self) -> Result<Json, String>
+    {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.bytes.get(self.at) == Some(&b'}') {
+            self.at += 1;
+            return Ok(Json::Object(members));
+        }
+
+        loop
+        {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.bytes.get(self.at) {
+                Some(b',') => self.at += 1,
+                Some(b'}') => {
+                    self.at += 1;
+                    return Ok(Json::Object(members));
+                },
+                _ => return Err(format!("expected ',' or '}}' at byte {}", self.at)),
+            }
+        }
+    }
+}
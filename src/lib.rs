@@ -0,0 +1,66 @@
+//! The Tower of Hanoi and Tower of London puzzles: the puzzle model, rule sets and solvers,
+//! procedural meshes, the actor system and the renderer with its OpenGL, recording and software backends.
+
+// Types follow the `new()` constructor convention, there are no `Default` impls alongside.
+#![allow(clippy::new_without_default)]
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+#[macro_use]
+extern crate gfx;
+extern crate shader_version;
+
+pub mod base_mesh_trait;
+pub mod mesh_desc;
+pub mod mesh_error;
+pub mod actor_trait;
+pub mod donut_mesh;
+pub mod donut_actor;
+pub mod stick_mesh;
+pub mod stick_actor;
+pub mod math;
+pub mod quaternion;
+pub mod transform;
+pub mod game_master;
+pub mod animator;
+pub mod tower_state;
+pub mod solver;
+pub mod game_settings;
+pub mod game_statistics;
+pub mod rule_set_trait;
+pub mod rule_set_registry;
+pub mod classic_rules;
+pub mod cyclic_rules;
+pub mod adjacent_rules;
+pub mod equal_size_rules;
+pub mod bicolor_rules;
+pub mod magnetic_rules;
+pub mod custom_rules;
+pub mod arrow_mesh;
+pub mod arrow_actor;
+pub mod master_trait;
+pub mod sphere_mesh;
+pub mod box_mesh;
+pub mod cylinder_mesh;
+pub mod cone_mesh;
+pub mod plane_mesh;
+pub mod washer_mesh;
+pub mod ball_actor;
+pub mod london_rules;
+pub mod london_master;
+pub mod trial_log;
+pub mod mini_map;
+pub mod mesh_cache;
+pub mod renderer;
+pub mod render_backend_trait;
+pub mod gl_backend;
+pub mod recording_backend;
+pub mod software_backend;
+pub mod ascii_view;
+pub mod mesh_export;
+pub mod json;
+pub mod base64;
+pub mod model_mesh;
+pub mod board_actor;
+pub mod scene_graph;
@@ -0,0 +1,370 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use piston::{Button, Key};
+use rand::Rng;
+
+use crate::{
+    actor_trait::{Actor, ActorBase},
+    animator::Anmiator,
+    ball_actor::ABall,
+    base_mesh_trait::IntoDesc,
+    game_settings::GameSettings,
+    game_statistics::GameStatistics,
+    london_rules::{LondonRules, BALLS_AMOUNT},
+    master_trait::Master,
+    mesh_error::MeshError,
+    mini_map::MiniMap,
+    renderer::Renderer,
+    rule_set_trait::RuleSet,
+    sphere_mesh::SphereMeshFactory,
+    stick_actor::AStick,
+    stick_mesh::StickMeshFactory,
+    tower_state::{Peg, TowerState, PEGS_AMOUNT},
+    trial_log::{TrialLog, TrialRecord},
+};
+
+
+
+const PEG_BOTTOM: f32 = -6.5;
+const POS_STICK: f32 = -12.;
+const DISTANCE_BETWEEN_STICKS: f32 = 15.;
+const STICK_RADIUS: f32 = 0.25;
+const BALL_RADIUS: f32 = 1.2;
+const MINI_MAP_OFFSET: [f32; 3] = [ 0., 11., POS_STICK ];
+
+// Trial difficulty in optimal moves when the session doesn't fix one with --puzzle.
+const DEFAULT_DISTANCES: std::ops::RangeInclusive<u64> = 2..=5;
+
+
+
+fn ball_position(peg: Peg, level: usize) -> vecmath::Vector3<f32>
+{
+    [ DISTANCE_BETWEEN_STICKS * peg as f32 - DISTANCE_BETWEEN_STICKS,
+      PEG_BOTTOM + BALL_RADIUS * (2 * level + 1) as f32,
+      POS_STICK ]
+}
+
+
+// Sticks are only as tall as the amount of balls they hold.
+fn stick_height(capacity: usize) -> f32
+{
+    BALL_RADIUS * 2. * capacity as f32 + 1.
+}
+
+
+// Relative to the foot of the peg.
+fn mini_map_ball_position(level: usize) -> vecmath::Vector3<f32>
+{
+    [ 0., BALL_RADIUS * (2 * level + 1) as f32, 0. ]
+}
+
+
+fn convert_key_to_peg(key: Key) -> Peg
+{
+    (key.code() - '1' as i32) as Peg
+}
+
+
+
+pub struct LondonMaster
+{
+    animator: Anmiator,
+    rules: LondonRules,
+
+    balls: [Vec<ABall>; PEGS_AMOUNT],
+    sticks: Vec::<AStick>,
+
+    button_choice: Option<Key>,
+
+    target_state: TowerState,
+    mini_map: MiniMap,
+    mini_map_balls: Vec::<ABall>,
+
+    trial: u32,
+    trials: u32,
+    distance: Option<u64>,
+    budget_slack: u32,
+    optimal_moves: u32,
+    budget: u32,
+    start_layout: String,
+    planning_time: Option<Duration>,
+    statistics: GameStatistics,
+    log: Option<TrialLog>,
+
+    playing: bool,
+}
+
+
+
+impl LondonMaster
+{
+    pub fn new() -> Self
+    {
+        LondonMaster {
+            animator: Anmiator::new(),
+            rules: LondonRules::new(),
+
+            balls: [ Vec::new(), Vec::new(), Vec::new() ],
+            sticks: Vec::new(),
+
+            button_choice: None,
+
+            target_state: TowerState::empty(),
+            mini_map: MiniMap::new(MINI_MAP_OFFSET),
+            mini_map_balls: Vec::new(),
+
+            trial: 0,
+            trials: 0,
+            distance: None,
+            budget_slack: 0,
+            optimal_moves: 0,
+            budget: 0,
+            start_layout: String::new(),
+            planning_time: None,
+            statistics: GameStatistics::new(),
+            log: None,
+
+            playing: true,
+        }
+    }
+
+
+
+    pub fn initialize(&mut self,
+                      settings: &GameSettings,
+                      renderer: &mut Renderer) -> Result<(), MeshError>
+    {
+        self.trials = settings.trials;
+        self.distance = settings.puzzle_distance;
+        self.budget_slack = settings.budget_slack;
+        self.log = Some(TrialLog::open(&settings.trial_log));
+
+        for peg in 0..PEGS_AMOUNT
+        {
+            let height = stick_height(self.rules.capacities[peg]);
+            let mut pos = ball_position(peg, 0);
+            pos[1] = PEG_BOTTOM + height * 0.5;
+
+            let stick_factory = StickMeshFactory::sized(STICK_RADIUS, height);
+
+            let stick = AStick::initialize(stick_factory.into_desc(), renderer)?;
+            <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
+            <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(), pos);
+
+            self.sticks.push(stick);
+
+            let stick = AStick::initialize(stick_factory.into_desc(), renderer)?;
+            <AStick as Actor>::rotate_x(&mut stick.actor_base.borrow_mut(), std::f32::consts::PI * 0.5);
+            <AStick as Actor>::set_position(&mut stick.actor_base.borrow_mut(), [ 0., height * 0.5, 0. ]);
+
+            self.mini_map.add_peg(pos[0], stick);
+        }
+
+        let start_state = self.rules.start_state(settings);
+        let sphere_factory = SphereMeshFactory::new(BALL_RADIUS);
+
+        for (peg, balls) in start_state.pegs.iter().enumerate()
+        {
+            for (level, ball) in balls.iter().enumerate()
+            {
+                let mut actor = ABall::initialize(sphere_factory.into_desc(), renderer)?;
+
+                <ABall as Actor>::set_position(&mut actor.actor_base.borrow_mut(), ball_position(peg, level));
+                actor.actor_base.borrow_mut().color = self.rules.donut_color(ball).unwrap();
+                actor.ball_color = ball.group;
+
+                self.balls[peg].push(actor);
+            }
+        }
+
+        for color in 0..BALLS_AMOUNT
+        {
+            let mut actor = ABall::initialize(sphere_factory.into_desc(), renderer)?;
+
+            actor.actor_base.borrow_mut().color = self.rules.donut_color(&LondonRules::ball(color)).unwrap();
+            actor.ball_color = color;
+
+            self.mini_map_balls.push(actor);
+        }
+
+        println!("Tower of London: {} trials, press 1-3 to pick a peg and again to drop the ball there.", self.trials);
+
+        self.start_trial();
+
+        Ok(())
+    }
+
+
+
+    // Every trial starts from where the previous one left the balls.
+    fn start_trial(&mut self)
+    {
+        let start_state = self.current_state();
+        let distance = self.distance.unwrap_or_else(|| rand::thread_rng().gen_range(DEFAULT_DISTANCES));
+
+        self.trial += 1;
+        self.target_state = self.rules.random_target(&start_state, 0, distance);
+        self.optimal_moves = self.rules
+                                 .plan_moves(&start_state, &self.target_state)
+                                 .map_or(0, |moves| moves.len() as u32);
+        self.budget = self.optimal_moves + self.budget_slack;
+        self.start_layout = LondonRules::layout_name(&start_state);
+        self.planning_time = None;
+        self.statistics = GameStatistics::new();
+        self.button_choice = None;
+
+        for (peg, balls) in self.target_state.pegs.iter().enumerate()
+        {
+            for (level, ball) in balls.iter().enumerate()
+            {
+                let actor = self.mini_map_balls.iter().find(|actor| actor.ball_color == ball.group).unwrap();
+
+                self.mini_map.place(peg, &actor.actor_base, mini_map_ball_position(level));
+            }
+        }
+
+        println!("Trial {}/{}: reach this arrangement in at most {} moves:\n{}",
+                 self.trial,
+                 self.trials,
+                 self.budget,
+                 self.rules.describe(&self.target_state));
+    }
+
+
+
+    fn finish_trial(&mut self, solved: bool)
+    {
+        let record = TrialRecord {
+            trial: self.trial,
+            start: self.start_layout.clone(),
+            target: LondonRules::layout_name(&self.target_state),
+            optimal_moves: self.optimal_moves,
+            budget: self.budget,
+            moves: self.statistics.moves,
+            invalid_moves: self.statistics.invalid_moves,
+            solved,
+            planning_time: self.planning_time,
+            total_time: self.statistics.elapsed(),
+        };
+
+        if let Some(log) = &mut self.log {
+            log.write(&record);
+        }
+
+        if self.trial < self.trials {
+            self.start_trial();
+            return;
+        }
+
+        self.playing = false;
+        println!("The session is over, thank you!");
+    }
+
+
+
+    fn choose_peg(&mut self, key: Key)
+    {
+        let Some(first) = self.button_choice.take() else {
+            self.button_choice = Some(key);
+            return;
+        };
+
+        let from = convert_key_to_peg(first);
+        let to = convert_key_to_peg(key);
+
+        if !self.rules.is_move_legal(&self.current_state(), from, to) {
+            self.statistics.invalid_moves += 1;
+            println!("The move is invalid.");
+            return;
+        }
+
+        if self.planning_time.is_none() {
+            self.planning_time = Some(self.statistics.elapsed());
+        }
+
+        self.statistics.moves += 1;
+
+        let ball = self.balls[from].pop().unwrap();
+        let mut target = <ABall as Actor>::get_transform(&ball.actor_base.borrow());
+        target.translation = ball_position(to, self.balls[to].len());
+
+        self.animator.queue_animation(ball.actor_base.clone(), target);
+
+        self.balls[to].push(ball);
+    }
+
+}
+
+
+
+impl Master for LondonMaster
+{
+    fn update(&mut self, button: Option<Button>)
+    {
+        if !self.playing {
+            return
+        }
+
+        self.balls.iter_mut().flatten().for_each(|actor| actor.update());
+        self.sticks.iter_mut().for_each(|actor| actor.update());
+        self.mini_map.update();
+        self.mini_map_balls.iter_mut().for_each(|actor| actor.update());
+
+        if self.animator.is_in_animation() {
+            self.animator.update();
+            return;
+        }
+
+        if self.current_state() == self.target_state {
+            self.finish_trial(true);
+            return;
+        }
+
+        if self.statistics.moves >= self.budget {
+            println!("The move budget is used up.");
+            self.finish_trial(false);
+            return;
+        }
+
+        if let Some(Button::Keyboard(key @ (Key::D1 | Key::D2 | Key::D3))) = button {
+            self.choose_peg(key);
+        }
+    }
+
+    fn visit_actors(&self, visit: &mut dyn FnMut(&ActorBase))
+    {
+        self.balls.iter().flatten().for_each(|actor| visit(&actor.actor_base.borrow()));
+        self.sticks.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+        self.mini_map.visit_actors(visit);
+        self.mini_map_balls.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+    }
+
+    fn current_state(&self) -> TowerState
+    {
+        let mut state = TowerState::empty();
+
+        for (peg, balls) in self.balls.iter().enumerate()
+        {
+            state.pegs[peg] = balls.iter().map(|ball| LondonRules::ball(ball.ball_color)).collect();
+        }
+
+        state
+    }
+
+    fn is_busy(&self) -> bool
+    {
+        self.playing && self.animator.is_in_animation()
+    }
+
+    fn is_playing(&self) -> bool
+    {
+        self.playing
+    }
+
+    fn statistics(&self) -> &GameStatistics
+    {
+        &self.statistics
+    }
+}
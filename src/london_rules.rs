@@ -0,0 +1,109 @@
+use crate::{
+    game_settings::GameSettings,
+    rule_set_trait::{self, RuleSet},
+    tower_state::{Disk, Peg, TowerState, PEGS_AMOUNT},
+};
+
+
+pub const BALLS_AMOUNT: u8 = 3;
+
+const BALL_COLORS: [[f32; 4]; BALLS_AMOUNT as usize] = [ [ 0.85, 0.15, 0.15, 1.0 ],
+                                                         [ 0.15, 0.7, 0.2, 1.0 ],
+                                                         [ 0.15, 0.3, 0.9, 1.0 ] ];
+const BALL_NAMES: [&str; BALLS_AMOUNT as usize] = [ "red", "green", "blue" ];
+
+
+// Tower of London: equally sized balls told apart by colour, the pegs only hold so many balls.
+// A ball is a disk of width 1 whose group is its colour.
+pub struct LondonRules
+{
+    pub capacities: [usize; PEGS_AMOUNT],
+}
+
+
+
+impl LondonRules
+{
+    pub fn new() -> Self
+    {
+        LondonRules {
+            capacities: [ 3, 2, 1 ],
+        }
+    }
+
+
+
+    pub fn ball(color: u8) -> Disk
+    {
+        Disk { group: color, ..Disk::new(1) }
+    }
+
+
+
+    /// Short form of the layout used in the trial log, e.g. "RG/B/" for red under green on the
+    /// first peg and blue on the second one.
+    pub fn layout_name(state: &TowerState) -> String
+    {
+        let pegs: Vec<String> = state.pegs.iter()
+                                          .map(|balls| balls.iter().map(|ball| ['R', 'G', 'B'][ball.group as usize]).collect())
+                                          .collect();
+
+        pegs.join("/")
+    }
+
+
+
+    /// The layout spelled out for the participant, one peg per line from the bottom ball up.
+    pub fn describe(&self, state: &TowerState) -> String
+    {
+        let mut lines = String::new();
+
+        for (peg, balls) in state.pegs.iter().enumerate()
+        {
+            let names: Vec<&str> = balls.iter().map(|ball| BALL_NAMES[ball.group as usize]).collect();
+            lines += &format!("  peg {} (holds {}): [{}]\n", peg + 1, self.capacities[peg], names.join(", "));
+        }
+
+        lines
+    }
+}
+
+
+
+impl RuleSet for LondonRules
+{
+    fn name(&self) -> &str
+    {
+        "london"
+    }
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, true) && state.pegs[to].len() < self.capacities[to]
+    }
+
+
+    fn allows_equal_widths(&self) -> bool
+    {
+        true
+    }
+
+
+    // The usual starting layout, red under green on the tall peg and blue on the middle one.
+    fn start_state(&self, _settings: &GameSettings) -> TowerState
+    {
+        let mut state = TowerState::empty();
+
+        state.pegs[0] = vec![ LondonRules::ball(0), LondonRules::ball(1) ];
+        state.pegs[1] = vec![ LondonRules::ball(2) ];
+
+        state
+    }
+
+
+    fn donut_color(&self, disk: &Disk) -> Option<[f32; 4]>
+    {
+        BALL_COLORS.get(disk.group as usize).copied()
+    }
+}
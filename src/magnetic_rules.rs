@@ -0,0 +1,413 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    rule_set_trait::{self, RuleSet},
+    solver::AutoGameMove,
+    tower_state::{Disk, Peg, TowerState, PEGS_AMOUNT},
+};
+
+
+const NORTH_UP_COLOR: [f32; 4] = [ 0.85, 0.2, 0.2, 1.0 ];
+const SOUTH_UP_COLOR: [f32; 4] = [ 0.2, 0.35, 0.85, 1.0 ];
+
+
+// Disks are magnets that flip over on every move, touching faces have to differ.
+pub struct MagneticRules;
+
+
+
+impl RuleSet for MagneticRules
+{
+    fn name(&self) -> &str
+    {
+        "magnetic"
+    }
+
+
+    // The moved disk flips on the way, so its bottom face ends up being the north one
+    // exactly when its north face points up now.
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool
+    {
+        rule_set_trait::fits_by_width(state, from, to, false)
+            && state.top(to).is_none_or(|below| below.north_up != state.top(from).unwrap().north_up)
+    }
+
+
+    fn apply_move(&self, state: &mut TowerState, from: Peg, to: Peg)
+    {
+        state.move_disk(from, to);
+
+        let moved = state.pegs[to].last_mut().unwrap();
+        moved.north_up = !moved.north_up;
+    }
+
+
+    // Legal stacks always point the same face up, whichever it ends up being.
+    fn is_won(&self, state: &TowerState, target: &TowerState) -> bool
+    {
+        state.same_layout(target)
+    }
+
+
+    fn solve(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        let mut solver = MagneticSolver::new(from, to)?;
+        let disks = solver.from.len();
+
+        solver.cost(disks, [ None; PEGS_AMOUNT ], Start::Layout, End::Layout)?;
+
+        let mut moves = VecDeque::new();
+        solver.emit(disks, [ None; PEGS_AMOUNT ], Start::Layout, End::Layout, &mut moves);
+
+        Some(moves)
+    }
+
+
+    // The solver goes through every pair of stick faces per disk, it slows down quickly.
+    fn max_disks_amount(&self) -> i32
+    {
+        12
+    }
+
+
+    fn donut_color(&self, disk: &Disk) -> Option<[f32; 4]>
+    {
+        match disk.north_up
+        {
+            true => Some(NORTH_UP_COLOR),
+            false => Some(SOUTH_UP_COLOR),
+        }
+    }
+}
+
+
+
+// The face every stick shows to a disk put on it, `None` for an empty one.
+type Bases = [Option<bool>; PEGS_AMOUNT];
+
+
+// Where the disks of a level start, a tower with the given face up or the layout being solved.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Start
+{
+    Tower(Peg, bool),
+    Layout,
+}
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum End
+{
+    Tower(Peg, bool),
+    Layout,
+}
+
+
+// The largest disk of a level and the tower of all smaller ones, with the faces they show up.
+#[derive(Clone, Copy, PartialEq)]
+struct Node
+{
+    disk: Peg,
+    disk_north: bool,
+    tower: Peg,
+    tower_north: bool,
+}
+
+
+#[derive(Clone, Copy)]
+enum Step
+{
+    Source(Node),
+    Disk(Node, Node),
+    Tower(Node, Node),
+}
+
+
+enum Plan
+{
+    // The largest disk stays where it is, only the smaller ones are solved.
+    Stay,
+    Path(Vec<Step>),
+}
+
+
+
+const NODES: usize = PEGS_AMOUNT * 2 * PEGS_AMOUNT * 2;
+
+
+
+impl Node
+{
+    fn index(&self) -> usize
+    {
+        ((self.disk * 2 + self.disk_north as usize) * PEGS_AMOUNT + self.tower) * 2 + self.tower_north as usize
+    }
+
+
+    fn from_index(index: usize) -> Self
+    {
+        Node {
+            disk: index / 12,
+            disk_north: index / 6 % 2 == 1,
+            tower: index / 2 % PEGS_AMOUNT,
+            tower_north: index % 2 == 1,
+        }
+    }
+
+
+    // The smaller disks stand on the largest one or on whatever is below the level.
+    fn bases(&self, bases: Bases) -> Bases
+    {
+        let mut bases = bases;
+        bases[self.disk] = Some(self.disk_north);
+
+        bases
+    }
+}
+
+
+
+// Optimal magnetic moves between any two layouts of the same disks.
+//
+// Whenever the largest disk of a level moves, every smaller disk sits in a tower on the third stick.
+// So a level is a shortest path over where the largest disk and the tower of the smaller ones are
+// and which faces they show, moving the tower costs what the level below says. The faces of the
+// disks under a level only matter through the face each stick shows, which keeps the memo small.
+struct MagneticSolver
+{
+    // Stick and face of every disk, the narrowest first.
+    from: Vec<(Peg, bool)>,
+    target: Vec<Peg>,
+
+    costs: HashMap<(usize, Bases, Start, End), Option<u64>>,
+}
+
+
+
+impl MagneticSolver
+{
+    fn new(from: &TowerState, to: &TowerState) -> Option<Self>
+    {
+        let disks = |state: &TowerState| {
+            let mut disks: Vec<(i32, Peg, bool)> = state.pegs
+                                                        .iter()
+                                                        .enumerate()
+                                                        .flat_map(|(peg, disks)| disks.iter().map(move |disk| (disk.width, peg, disk.north_up)))
+                                                        .collect();
+            disks.sort_by_key(|(width, _, _)| *width);
+            disks
+        };
+
+        let from = disks(from);
+        let to = disks(to);
+
+        if from.len() != to.len() || from.iter().zip(to.iter()).any(|(a, b)| a.0 != b.0) {
+            return None;
+        }
+
+        Some(MagneticSolver {
+            from: from.iter().map(|(_, peg, north)| (*peg, *north)).collect(),
+            target: to.iter().map(|(_, peg, _)| *peg).collect(),
+            costs: HashMap::new(),
+        })
+    }
+
+
+
+    // Least moves of the `level` narrowest disks from `start` to `end`, `None` if it can't be done.
+    fn cost(&mut self, level: usize, bases: Bases, start: Start, end: End) -> Option<u64>
+    {
+        if level == 0 {
+            return Some(0);
+        }
+
+        if let Some(cost) = self.costs.get(&(level, bases, start, end)) {
+            return *cost;
+        }
+
+        let cost = self.search(level, bases, start, end).map(|(cost, _)| cost);
+        self.costs.insert((level, bases, start, end), cost);
+
+        cost
+    }
+
+
+
+    fn search(&mut self, level: usize, bases: Bases, start: Start, end: End) -> Option<(u64, Plan)>
+    {
+        let below = level - 1;
+        let mut dist = [ None::<u64>; NODES ];
+        let mut steps = [ None::<Step>; NODES ];
+
+        match start
+        {
+            Start::Tower(peg, north) => {
+                let node = Node { disk: peg, disk_north: north, tower: peg, tower_north: north };
+                dist[node.index()] = Some(0);
+                steps[node.index()] = Some(Step::Source(node));
+            },
+            Start::Layout => {
+                let (disk, disk_north) = self.from[below];
+
+                for tower in 0..PEGS_AMOUNT
+                {
+                    for tower_north in [ true, false ]
+                    {
+                        let node = Node { disk, disk_north, tower, tower_north };
+                        let gathered = self.cost(below, node.bases(bases), Start::Layout, End::Tower(tower, tower_north));
+
+                        if let Some(cost) = gathered {
+                            dist[node.index()] = Some(cost);
+                            steps[node.index()] = Some(Step::Source(node));
+                        }
+                    }
+                }
+            },
+        }
+
+        let mut done = [ false; NODES ];
+
+        while let Some(index) = (0..NODES).filter(|i| !done[*i] && dist[*i].is_some()).min_by_key(|i| dist[*i])
+        {
+            done[index] = true;
+
+            let node = Node::from_index(index);
+            let reached = dist[index].unwrap();
+            let mut relax = |next: Node, cost: u64, step: Step| {
+                let cost = reached.saturating_add(cost);
+
+                if dist[next.index()].is_none_or(|known| cost < known) {
+                    dist[next.index()] = Some(cost);
+                    steps[next.index()] = Some(step);
+                }
+            };
+
+            // The largest disk flips over and may only land on a face different from the one it shows up now.
+            for to in (0..PEGS_AMOUNT).filter(|to| *to != node.disk && *to + node.disk + node.tower == 3)
+            {
+                if bases[to].is_none_or(|face| face != node.disk_north) {
+                    let next = Node { disk: to, disk_north: !node.disk_north, ..node };
+                    relax(next, 1, Step::Disk(node, next));
+                }
+            }
+
+            for to in (0..PEGS_AMOUNT).filter(|to| *to != node.tower)
+            {
+                for tower_north in [ true, false ]
+                {
+                    let moved = self.cost(below,
+                                          node.bases(bases),
+                                          Start::Tower(node.tower, node.tower_north),
+                                          End::Tower(to, tower_north));
+
+                    if let Some(cost) = moved {
+                        let next = Node { tower: to, tower_north, ..node };
+                        relax(next, cost, Step::Tower(node, next));
+                    }
+                }
+            }
+        }
+
+        let mut best: Option<(u64, Option<Node>)> = None;
+
+        for node in (0..NODES).map(Node::from_index).filter(|node| dist[node.index()].is_some())
+        {
+            let finish = match end {
+                End::Tower(peg, north) if node == (Node { disk: peg, disk_north: north, tower: peg, tower_north: north }) => Some(0),
+                End::Tower(..) => None,
+                End::Layout if node.disk == self.target[below] => {
+                    self.cost(below, node.bases(bases), Start::Tower(node.tower, node.tower_north), End::Layout)
+                },
+                End::Layout => None,
+            };
+
+            if let Some(cost) = finish.map(|finish| dist[node.index()].unwrap().saturating_add(finish))
+                && best.is_none_or(|(known, _)| cost < known) {
+                best = Some((cost, Some(node)));
+            }
+        }
+
+        if start == Start::Layout && end == End::Layout && self.from[below].0 == self.target[below] {
+            let (disk, disk_north) = self.from[below];
+            let bases = Node { disk, disk_north, tower: disk, tower_north: disk_north }.bases(bases);
+
+            if let Some(cost) = self.cost(below, bases, Start::Layout, End::Layout)
+                && best.is_none_or(|(known, _)| cost <= known) {
+                best = Some((cost, None));
+            }
+        }
+
+        let (cost, last) = best?;
+
+        let Some(mut node) = last else {
+            return Some((cost, Plan::Stay));
+        };
+
+        let mut path = Vec::new();
+
+        loop
+        {
+            let step = steps[node.index()].unwrap();
+            path.push(step);
+
+            match step
+            {
+                Step::Source(_) => break,
+                Step::Disk(previous, _) | Step::Tower(previous, _) => node = previous,
+            }
+        }
+
+        path.reverse();
+
+        Some((cost, Plan::Path(path)))
+    }
+
+
+
+    fn emit(&mut self, level: usize, bases: Bases, start: Start, end: End, moves: &mut VecDeque<AutoGameMove>)
+    {
+        if level == 0 {
+            return;
+        }
+
+        let below = level - 1;
+        let (_, plan) = self.search(level, bases, start, end).unwrap();
+
+        let path = match plan {
+            Plan::Stay => {
+                let (disk, disk_north) = self.from[below];
+                let node = Node { disk, disk_north, tower: disk, tower_north: disk_north };
+
+                return self.emit(below, node.bases(bases), Start::Layout, End::Layout, moves);
+            },
+            Plan::Path(path) => path,
+        };
+
+        for step in path.iter()
+        {
+            match *step
+            {
+                Step::Source(node) if start == Start::Layout => {
+                    self.emit(below, node.bases(bases), Start::Layout, End::Tower(node.tower, node.tower_north), moves);
+                },
+                Step::Source(_) => (),
+                Step::Disk(from, to) => moves.push_back(AutoGameMove { from: from.disk, to: to.disk }),
+                Step::Tower(from, to) => {
+                    self.emit(below,
+                              from.bases(bases),
+                              Start::Tower(from.tower, from.tower_north),
+                              End::Tower(to.tower, to.tower_north),
+                              moves);
+                },
+            }
+        }
+
+        let last = match path.last().unwrap() {
+            Step::Source(node) | Step::Disk(_, node) | Step::Tower(_, node) => *node,
+        };
+
+        if end == End::Layout {
+            self.emit(below, last.bases(bases), Start::Tower(last.tower, last.tower_north), End::Layout, moves);
+        }
+    }
+}
@@ -1,20 +1,16 @@
 use camera_controllers::{FirstPerson, FirstPersonSettings};
-mod base_mesh_trait;
-mod actor_trait;
-mod donut_mesh;
-mod donut_actor;
-mod stick_mesh;
-mod stick_actor;
-mod math;
-mod game_master;
-mod animator;
+use kolo::{
+    game_settings,
+    gl_backend,
+    master_trait,
+    mesh_export,
+    renderer,
+    software_backend,
+};
 
 extern crate piston_window;
 extern crate vecmath;
 extern crate camera_controllers;
-#[macro_use]
-extern crate gfx;
-extern crate shader_version;
 
 #[cfg(feature = "include_sdl2")]
 extern crate sdl2_window;
@@ -30,8 +26,11 @@ use glfw_window::GlfwWindow as AppWindow;
 #[cfg(feature = "include_glutin")]
 use glutin_window::GlutinWindow as AppWindow;
 
-use opengl_graphics::{ GlGraphics, Texture, TextureSettings};
-use graphics::{Context, Graphics, ImageSize};
+// Pressing E writes the scene to this path as OBJ and glTF.
+const SCENE_EXPORT_PATH: &str = "kolo_scene";
+// Pressing P renders the scene on the CPU into this image.
+const SCREENSHOT_PATH: &str = "kolo_screenshot.png";
+
 
 fn main() 
 {
@@ -48,7 +47,6 @@ fn main()
                                                   .unwrap();
     window.set_capture_cursor(false);
 
-    let mut factory = window.factory.clone();
 
     let get_projection = |w: &PistonWindow| {
         let draw_size = w.window.draw_size();
@@ -63,9 +61,21 @@ fn main()
     let mut projection = get_projection(&window);
 
 
-    let donuts_amount = 5;
-    let mut game_master = game_master::GameMaster::new();
-    game_master.initialize(donuts_amount, &opengl, &window, &mut factory);
+    let settings = game_settings::GameSettings::from_args(std::env::args());
+    let mut renderer = renderer::Renderer::new();
+
+    renderer.cache_mut().use_models(&settings);
+
+    let mut backend = gl_backend::GlBackend::new(&opengl, &window.factory);
+
+    let mut game_master = match master_trait::create_master(&settings, &mut renderer)
+    {
+        Ok(master) => master,
+        Err(error) => {
+            println!("Can't build the scene: {}", error);
+            return;
+        },
+    };
 
 
     let mut first_person_camera = FirstPerson::new(
@@ -79,22 +89,48 @@ fn main()
         // first_person.event(&e);
 
         game_master.update(e.press_args());
+
+        if let Some(Button::Keyboard(Key::E)) = e.press_args()
+        {
+            let scene = mesh_export::collect_scene(game_master.as_ref(), renderer.cache());
+
+            match mesh_export::write_scene(&scene, SCENE_EXPORT_PATH) {
+                Ok(()) => println!("The scene is exported to {0}.obj and {0}.gltf", SCENE_EXPORT_PATH),
+                Err(error) => println!("Can't export the scene: {}", error),
+            }
+        }
+
+        if let Some(Button::Keyboard(Key::P)) = e.press_args()
+        {
+            let draw_size = window.window.draw_size();
+            let mut screenshot = software_backend::SoftwareBackend::new(draw_size.width as u32, draw_size.height as u32);
+
+            renderer.begin(&first_person_camera.camera(0.).orthogonal(), &projection);
+            game_master.render(&mut renderer);
+            renderer.submit(&mut screenshot);
+
+            match screenshot.save(SCREENSHOT_PATH) {
+                Ok(()) => println!("The screenshot is saved to {}", SCREENSHOT_PATH),
+                Err(error) => println!("Can't save the screenshot: {}", error),
+            }
+        }
         
         window.draw_3d(&e, | window | {
-            let args = e.render_args().clone().unwrap();
+            let args = e.render_args().unwrap();
 
             window.encoder.clear(&window.output_color, [0.0, 0.0, 0.0, 1.0]);
             window.encoder.clear_depth(&window.output_stencil, 1.0);
 
 
-            game_master.render(window, &first_person_camera.camera(args.ext_dt).orthogonal(), projection);
+            renderer.begin(&first_person_camera.camera(args.ext_dt).orthogonal(), &projection);
+            game_master.render(&mut renderer);
+            renderer.submit(&mut backend.frame(window));
 
         });
 
         if e.resize_args().is_some() 
         {
             projection = get_projection(&window);
-            game_master.resize(&mut window);
         }
     }
 }
@@ -0,0 +1,69 @@
+use piston::Button;
+
+use crate::{
+    actor_trait::ActorBase,
+    game_master::GameMaster,
+    game_settings::{GameMode, GameSettings},
+    game_statistics::GameStatistics,
+    london_master::LondonMaster,
+    mesh_error::MeshError,
+    renderer::Renderer,
+    tower_state::TowerState,
+};
+
+
+
+// One game mode driven by the main loop.
+pub trait Master
+{
+    fn update(&mut self, button: Option<Button>);
+
+
+    // Every actor of the scene, in no particular order.
+    fn visit_actors(&self, visit: &mut dyn FnMut(&ActorBase));
+
+
+    // Hands every actor to the renderer, the main loop submits them to a backend afterwards.
+    fn render(&mut self, renderer: &mut Renderer)
+    {
+        self.visit_actors(&mut |base| renderer.push(base));
+    }
+
+
+    // The logical layout of the pegs, moves being animated count as done.
+    fn current_state(&self) -> TowerState;
+
+
+    // Animating a move or playing on its own, the master ignores the buttons until it's done.
+    fn is_busy(&self) -> bool;
+
+
+    fn is_playing(&self) -> bool;
+
+
+    // Of the current game, or of the current trial in the Tower of London.
+    fn statistics(&self) -> &GameStatistics;
+}
+
+
+
+/// The master of the mode picked in the settings, with its scene built.
+pub fn create_master(settings: &GameSettings,
+                     renderer: &mut Renderer) -> Result<Box<dyn Master>, MeshError>
+{
+    match settings.mode
+    {
+        GameMode::Hanoi => {
+            let mut master = GameMaster::new();
+            master.initialize(settings, renderer)?;
+
+            Ok(Box::new(master))
+        },
+        GameMode::London => {
+            let mut master = LondonMaster::new();
+            master.initialize(settings, renderer)?;
+
+            Ok(Box::new(master))
+        },
+    }
+}
@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+
+use vecmath::{Matrix4, Vector3};
     
 pub const TWO_PI: f32 = std::f32::consts::PI * 2.;
 
@@ -10,13 +12,16 @@ pub fn pos_translation(matrix: &mut vecmath::Matrix4<f32>, position: vecmath::Ve
     matrix[3][2] = position[2]; // z
 }
 
-pub fn scale_translation(matrix: &mut vecmath::Matrix4<f32>, position: vecmath::Vector3<f32>) 
+// Scales the axes of `matrix` in place, a rotation in it is kept.
+pub fn scale_translation(matrix: &mut vecmath::Matrix4<f32>, scale: vecmath::Vector3<f32>) 
 {
-    matrix[0][0] = position[0]; // x
-    matrix[1][1] = position[1]; // y
-    matrix[2][2] = position[2]; // z
+    for (column, scale) in matrix.iter_mut().zip(scale)
+    {
+        column.iter_mut().take(3).for_each(|value| *value *= scale);
+    }
 }
 
+// The X, Y and Z rotations turn clockwise looking down the axis, `rotation_axis(X, -angle)` is `rotation_x(angle)`.
 pub fn rotation_y(angle_rad: f32) -> [[f32; 4]; 4] 
 {
     let cos = angle_rad.cos();
@@ -50,3 +55,237 @@ pub fn rotation_z(angle_rad: f32) -> [[f32; 4]; 4]
      [  0.0,  0.0,  0.0,  1.0],]
 }
 
+
+
+
+pub fn translation(offset: Vector3<f32>) -> Matrix4<f32>
+{
+    let mut matrix = vecmath::mat4_id();
+    pos_translation(&mut matrix, offset);
+
+    matrix
+}
+
+
+
+pub fn scaling(scale: Vector3<f32>) -> Matrix4<f32>
+{
+    let mut matrix = vecmath::mat4_id();
+    scale_translation(&mut matrix, scale);
+
+    matrix
+}
+
+
+
+// Counter-clockwise looking down `axis`, like `Quaternion::from_axis_angle`.
+pub fn rotation_axis(axis: Vector3<f32>, angle_rad: f32) -> Matrix4<f32>
+{
+    let [x, y, z] = vecmath::vec3_normalized(axis);
+    let (sin, cos) = angle_rad.sin_cos();
+    let c = 1. - cos;
+
+    [[ x * x * c + cos,     y * x * c + z * sin, z * x * c - y * sin, 0. ],
+     [ x * y * c - z * sin, y * y * c + cos,     z * y * c + x * sin, 0. ],
+     [ x * z * c + y * sin, y * z * c - x * sin, z * z * c + cos,     0. ],
+     [ 0.,                  0.,                  0.,                  1. ],]
+}
+
+
+
+// The view matrix of a camera at `eye`, looking down its -Z axis at `target`.
+pub fn look_at(eye: Vector3<f32>, target: Vector3<f32>, up: Vector3<f32>) -> Matrix4<f32>
+{
+    let f = vecmath::vec3_normalized(vecmath::vec3_sub(eye, target));
+    let r = vecmath::vec3_normalized(vecmath::vec3_cross(up, f));
+    let u = vecmath::vec3_cross(f, r);
+
+    [[ r[0], u[0], f[0], 0. ],
+     [ r[1], u[1], f[1], 0. ],
+     [ r[2], u[2], f[2], 0. ],
+     [ -vecmath::vec3_dot(r, eye), -vecmath::vec3_dot(u, eye), -vecmath::vec3_dot(f, eye), 1. ],]
+}
+
+
+
+// OpenGL clip space, the depth goes from -1 at `near` to 1 at `far`.
+pub fn perspective(fov_y_rad: f32, aspect: f32, near: f32, far: f32) -> Matrix4<f32>
+{
+    let f = 1. / (fov_y_rad * 0.5).tan();
+
+    [[ f / aspect, 0., 0.,                                0. ],
+     [ 0.,         f,  0.,                                0. ],
+     [ 0.,         0., (far + near) / (near - far),      -1. ],
+     [ 0.,         0., 2. * far * near / (near - far),    0. ],]
+}
+
+
+
+pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix4<f32>
+{
+    [[ 2. / (right - left), 0., 0., 0. ],
+     [ 0., 2. / (top - bottom), 0., 0. ],
+     [ 0., 0., -2. / (far - near), 0. ],
+     [ -(right + left) / (right - left), -(top + bottom) / (top - bottom), -(far + near) / (far - near), 1. ],]
+}
+
+
+
+pub fn lerp(a: Vector3<f32>, b: Vector3<f32>, t: f32) -> Vector3<f32>
+{
+    vecmath::vec3_add(a, vecmath::vec3_scale(vecmath::vec3_sub(b, a), t))
+}
+
+
+
+pub fn distance(a: Vector3<f32>, b: Vector3<f32>) -> f32
+{
+    vecmath::vec3_len(vecmath::vec3_sub(b, a))
+}
+
+
+
+pub fn min(a: Vector3<f32>, b: Vector3<f32>) -> Vector3<f32>
+{
+    [ a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2]) ]
+}
+
+
+
+pub fn max(a: Vector3<f32>, b: Vector3<f32>) -> Vector3<f32>
+{
+    [ a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2]) ]
+}
+
+
+
+// With the perspective divide, for projections too.
+pub fn transform_point(matrix: &Matrix4<f32>, point: Vector3<f32>) -> Vector3<f32>
+{
+    let [x, y, z, w] = vecmath::col_mat4_transform(*matrix, [ point[0], point[1], point[2], 1. ]);
+
+    [ x / w, y / w, z / w ]
+}
+
+
+
+// Directions aren't moved, only turned and scaled.
+pub fn transform_direction(matrix: &Matrix4<f32>, direction: Vector3<f32>) -> Vector3<f32>
+{
+    let [x, y, z, _] = vecmath::col_mat4_transform(*matrix, [ direction[0], direction[1], direction[2], 0. ]);
+
+    [ x, y, z ]
+}
+
+
+
+/// A half line, the hits are distances along the normalized direction.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Ray
+{
+    pub origin: Vector3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+
+
+impl Ray
+{
+    pub fn new(origin: Vector3<f32>, direction: Vector3<f32>) -> Self
+    {
+        Ray { origin, direction: vecmath::vec3_normalized(direction) }
+    }
+
+
+
+    pub fn at(&self, distance: f32) -> Vector3<f32>
+    {
+        vecmath::vec3_add(self.origin, vecmath::vec3_scale(self.direction, distance))
+    }
+
+
+
+    // Parallel planes and planes behind the origin are missed.
+    pub fn hit_plane(&self, point: Vector3<f32>, normal: Vector3<f32>) -> Option<f32>
+    {
+        let facing = vecmath::vec3_dot(self.direction, normal);
+
+        if facing.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let distance = vecmath::vec3_dot(vecmath::vec3_sub(point, self.origin), normal) / facing;
+
+        (distance >= 0.).then_some(distance)
+    }
+
+
+
+    // Where the ray gets into the sphere, 0 when it starts inside.
+    pub fn hit_sphere(&self, center: Vector3<f32>, radius: f32) -> Option<f32>
+    {
+        let to_center = vecmath::vec3_sub(center, self.origin);
+        let along = vecmath::vec3_dot(to_center, self.direction);
+        let off_squared = vecmath::vec3_dot(to_center, to_center) - along * along;
+        let radius_squared = radius * radius;
+
+        if off_squared > radius_squared {
+            return None;
+        }
+
+        let half_chord = (radius_squared - off_squared).sqrt();
+
+        match (along - half_chord, along + half_chord) {
+            (_, exit) if exit < 0. => None,
+            (entry, _) => Some(entry.max(0.)),
+        }
+    }
+
+
+
+    // Where the ray gets into the box between the corners `min` and `max`, 0 when it starts inside.
+    pub fn hit_aabb(&self, min: Vector3<f32>, max: Vector3<f32>) -> Option<f32>
+    {
+        let mut entry = 0f32;
+        let mut exit = f32::INFINITY;
+
+        for axis in 0..3
+        {
+            let inverse = 1. / self.direction[axis];
+            let a = (min[axis] - self.origin[axis]) * inverse;
+            let b = (max[axis] - self.origin[axis]) * inverse;
+
+            // Running along a face of the box makes 0 * inf, that's a graze and no hit.
+            if a.is_nan() || b.is_nan() {
+                return None;
+            }
+
+            entry = entry.max(a.min(b));
+            exit = exit.min(a.max(b));
+        }
+
+        (entry <= exit).then_some(entry)
+    }
+}
+
+
+
+// The world point of a point in normalized device coordinates, `view_proj` is the projection times the camera.
+pub fn unproject(ndc: Vector3<f32>, view_proj: &Matrix4<f32>) -> Vector3<f32>
+{
+    transform_point(&vecmath::mat4_inv(*view_proj), ndc)
+}
+
+
+
+// The ray from the camera through a pixel of the window, pixels count from the top left corner.
+pub fn pick_ray(pixel: [f32; 2], viewport: [f32; 2], view_proj: &Matrix4<f32>) -> Ray
+{
+    let x = pixel[0] / viewport[0] * 2. - 1.;
+    let y = 1. - pixel[1] / viewport[1] * 2.;
+
+    let near = unproject([ x, y, -1. ], view_proj);
+    let far = unproject([ x, y, 1. ], view_proj);
+
+    Ray::new(near, vecmath::vec3_sub(far, near))
+}
@@ -0,0 +1,194 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{
+    base_mesh_trait::{MeshData, MeshDesc},
+    game_settings::GameSettings,
+    mesh_error::MeshError,
+    model_mesh,
+};
+
+
+// Levels of detail built for every mesh, each one halves the segments of the previous.
+pub const LOD_LEVELS: u32 = 3;
+
+
+
+/// Index of a mesh in the cache, backends keep their own copy of the mesh under it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MeshHandle(pub usize);
+
+
+
+pub struct CachedMesh
+{
+    pub radius: f32,
+    // What the mesh is built from, the description and the model file if there is one.
+    pub name: String,
+    pub data: Rc<MeshData>,
+    // The handles of its levels of detail, the finest first, empty for the coarser levels themselves.
+    pub lods: Vec<MeshHandle>,
+}
+
+
+
+/// Meshes shared by every actor built from the same description, built once on the CPU
+/// and handed to the render backends by handle.
+pub struct MeshCache
+{
+    meshes: Vec<CachedMesh>,
+    // Keyed by the mesh name, the text form of the description lists every parameter.
+    handles: HashMap<String, MeshHandle>,
+    // Model files replacing every procedural mesh of a kind, with the triangles read from them.
+    models: HashMap<&'static str, (String, Rc<MeshData>)>,
+}
+
+
+
+impl MeshCache
+{
+    pub fn new() -> Self
+    {
+        MeshCache {
+            meshes: Vec::new(),
+            handles: HashMap::new(),
+            models: HashMap::new(),
+        }
+    }
+
+
+
+    /// Builds every mesh of the given kinds, like "stick", from the model at `path`.
+    /// The model is read once here and fitted to every description, nothing is replaced when it can't be used.
+    pub fn use_model(&mut self, kinds: &[&'static str], path: &str) -> Result<(), MeshError>
+    {
+        let model = Rc::new(model_mesh::load_model(path)?);
+
+        for kind in kinds
+        {
+            self.models.insert(kind, (path.to_string(), model.clone()));
+        }
+
+        Ok(())
+    }
+
+
+
+    /// Registers the disk, peg and board models of the settings, unusable files are reported and skipped.
+    pub fn use_models(&mut self, settings: &GameSettings)
+    {
+        for (path, kinds) in [ (&settings.disk_model, &[ "donut", "washer" ][..]),
+                               (&settings.peg_model, &[ "stick" ][..]),
+                               (&settings.board_model, &[ "box" ][..]) ]
+        {
+            let Some(path) = path else {
+                continue;
+            };
+
+            if let Err(error) = self.use_model(kinds, path) {
+                println!("{}, keeping the built-in mesh.", error);
+            }
+        }
+    }
+
+
+
+    pub fn has_model(&self, kind: &str) -> bool
+    {
+        self.models.contains_key(kind)
+    }
+
+
+
+    /// The mesh of `mesh_desc`, built with its levels of detail the first time it's asked for.
+    pub fn mesh(&mut self, mesh_desc: &MeshDesc) -> Result<MeshHandle, MeshError>
+    {
+        let handle = self.build(mesh_desc)?;
+
+        if self.meshes[handle.0].lods.is_empty() {
+            let mut levels = vec![ handle ];
+
+            // Models have only the detail they come with.
+            let levels_amount = match self.has_model(mesh_desc.kind()) {
+                true => 1,
+                false => LOD_LEVELS,
+            };
+
+            for level in 1..levels_amount
+            {
+                let lod_desc = mesh_desc.lod(level);
+
+                if lod_desc != mesh_desc.lod(level - 1) {
+                    levels.push(self.build(&lod_desc)?);
+                }
+            }
+
+            self.meshes[handle.0].lods = levels;
+        }
+
+        Ok(handle)
+    }
+
+
+
+    fn build(&mut self, mesh_desc: &MeshDesc) -> Result<MeshHandle, MeshError>
+    {
+        let model = self.models.get(mesh_desc.kind()).cloned();
+        let name = match &model {
+            Some((path, _)) => format!("{} model={}", mesh_desc, path),
+            None => mesh_desc.to_string(),
+        };
+
+        if let Some(handle) = self.handles.get(&name) {
+            return Ok(*handle);
+        }
+
+        let mesh_data = match &model {
+            Some((_, model)) => model_mesh::fit_model(model, &mesh_desc.factory().build_mesh()?),
+            None => mesh_desc.factory().build_mesh()?,
+        };
+        let handle = MeshHandle(self.meshes.len());
+
+        self.meshes.push(CachedMesh { radius: mesh_data.bounding_radius(), name: name.clone(), data: Rc::new(mesh_data), lods: Vec::new() });
+        self.handles.insert(name, handle);
+
+        Ok(handle)
+    }
+
+
+
+    /// The triangles `mesh_desc` is drawn with, models included.
+    pub fn mesh_data(&mut self, mesh_desc: &MeshDesc) -> Result<Rc<MeshData>, MeshError>
+    {
+        let handle = self.build(mesh_desc)?;
+
+        Ok(self.meshes[handle.0].data.clone())
+    }
+
+
+
+    pub fn get(&self, handle: MeshHandle) -> &CachedMesh
+    {
+        &self.meshes[handle.0]
+    }
+
+
+
+    /// The levels of detail of the mesh handed to actors as `handle`, just the mesh itself for the others.
+    pub fn lods(&self, handle: MeshHandle) -> Vec<MeshHandle>
+    {
+        match self.meshes[handle.0].lods.is_empty() {
+            true => vec![ handle ],
+            false => self.meshes[handle.0].lods.clone(),
+        }
+    }
+
+
+
+    pub fn meshes_amount(&self) -> usize
+    {
+        self.meshes.len()
+    }
+}
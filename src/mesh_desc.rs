@@ -0,0 +1,450 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::{
+    arrow_mesh::ArrowMeshFactory,
+    base_mesh_trait::MeshFactory,
+    box_mesh::BoxMeshFactory,
+    mesh_error::MeshError,
+    cone_mesh::ConeMeshFactory,
+    cylinder_mesh::CylinderMeshFactory,
+    donut_mesh::DonutMeshFactory,
+    plane_mesh::PlaneMeshFactory,
+    sphere_mesh::SphereMeshFactory,
+    stick_mesh::StickMeshFactory,
+    washer_mesh::WasherMeshFactory,
+};
+
+
+// Keeps the size arithmetic small, the size of the whole mesh is capped below.
+const MAX_SEGMENTS: isize = 4096;
+
+// Far more than any mesh of the game needs, a typo in a description can't ask for gigabytes.
+const MAX_VERTICES: usize = 1 << 20;
+const MAX_INDICES: usize = 6 << 20;
+
+
+
+fn positive(mesh: &'static str, parameter: &'static str, value: f32) -> Result<(), MeshError>
+{
+    match value > 0. && value.is_finite() {
+        true => Ok(()),
+        false => Err(MeshError::InvalidParameter { mesh, parameter, value, expected: "a positive number" }),
+    }
+}
+
+
+fn segments(mesh: &'static str, parameter: &'static str, value: isize, min: isize) -> Result<(), MeshError>
+{
+    match (min..=MAX_SEGMENTS).contains(&value) {
+        true => Ok(()),
+        false => Err(MeshError::InvalidParameter {
+            mesh,
+            parameter,
+            value: value as f32,
+            expected: match min {
+                1 => "from 1 to 4096",
+                2 => "from 2 to 4096",
+                _ => "from 3 to 4096",
+            },
+        }),
+    }
+}
+
+
+
+/// Everything needed to build a mesh again, one variant per mesh factory.
+/// Written as text it reads "donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=32".
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MeshDesc
+{
+    Donut { major_radius: f32, minor_radius: f32, segments_major: isize, segments_minor: isize },
+    Washer { outer_radius: f32, inner_radius: f32, thickness: f32, bevel: f32, segments: isize },
+    Stick { radius: f32, height: f32, segments: isize, height_segments: isize, top_rings: isize },
+    Sphere { radius: f32, segments: isize, rings: isize },
+    Box { width: f32, height: f32, depth: f32 },
+    Cylinder { radius: f32, height: f32, segments: isize, caps: bool },
+    Cone { radius: f32, height: f32, segments: isize },
+    Plane { width: f32, depth: f32, subdivisions: isize },
+    Arrow { length: f32, width: f32, thickness: f32 },
+}
+
+
+
+impl MeshDesc
+{
+    /// Factory building exactly the described mesh.
+    pub fn factory(&self) -> Box<dyn MeshFactory>
+    {
+        match *self
+        {
+            MeshDesc::Donut { major_radius, minor_radius, segments_major, segments_minor } => {
+                Box::new(DonutMeshFactory { major_radius, minor_radius, segments_major, segments_minor })
+            },
+            MeshDesc::Washer { outer_radius, inner_radius, thickness, bevel, segments } => {
+                Box::new(WasherMeshFactory { outer_radius, inner_radius, thickness, bevel, segments })
+            },
+            MeshDesc::Stick { radius, height, segments, height_segments, top_rings } => {
+                Box::new(StickMeshFactory { radius, height, segments, height_segments, top_rings })
+            },
+            MeshDesc::Sphere { radius, segments, rings } => {
+                Box::new(SphereMeshFactory { radius, segments, rings })
+            },
+            MeshDesc::Box { width, height, depth } => {
+                Box::new(BoxMeshFactory { width, height, depth })
+            },
+            MeshDesc::Cylinder { radius, height, segments, caps } => {
+                Box::new(CylinderMeshFactory { radius, height, segments, caps })
+            },
+            MeshDesc::Cone { radius, height, segments } => {
+                Box::new(ConeMeshFactory { radius, height, segments })
+            },
+            MeshDesc::Plane { width, depth, subdivisions } => {
+                Box::new(PlaneMeshFactory { width, depth, subdivisions })
+            },
+            MeshDesc::Arrow { length, width, thickness } => {
+                Box::new(ArrowMeshFactory { length, width, thickness })
+            },
+        }
+    }
+
+
+
+    /// Checks the parameters before anything gets built.
+    pub fn validate(&self) -> Result<(), MeshError>
+    {
+        let kind = self.fields().0;
+
+        match *self
+        {
+            MeshDesc::Donut { major_radius, minor_radius, segments_major, segments_minor } => {
+                positive(kind, "major_radius", major_radius)?;
+                positive(kind, "minor_radius", minor_radius)?;
+                segments(kind, "segments_major", segments_major, 3)?;
+                segments(kind, "segments_minor", segments_minor, 3)?;
+            },
+            MeshDesc::Washer { outer_radius, inner_radius, thickness, bevel, segments: ring_segments } => {
+                positive(kind, "outer_radius", outer_radius)?;
+                positive(kind, "thickness", thickness)?;
+                segments(kind, "segments", ring_segments, 3)?;
+
+                if !(0. ..outer_radius).contains(&inner_radius) {
+                    return Err(MeshError::InvalidParameter {
+                        mesh: kind,
+                        parameter: "inner_radius",
+                        value: inner_radius,
+                        expected: "at least 0 and below outer_radius",
+                    });
+                }
+
+                if !(bevel >= 0. && bevel.is_finite()) {
+                    return Err(MeshError::InvalidParameter { mesh: kind, parameter: "bevel", value: bevel, expected: "at least 0" });
+                }
+            },
+            MeshDesc::Stick { radius, height, segments: ring_segments, height_segments, top_rings } => {
+                positive(kind, "radius", radius)?;
+                positive(kind, "height", height)?;
+                segments(kind, "segments", ring_segments, 3)?;
+                segments(kind, "height_segments", height_segments, 1)?;
+                segments(kind, "top_rings", top_rings, 1)?;
+            },
+            MeshDesc::Sphere { radius, segments: ring_segments, rings } => {
+                positive(kind, "radius", radius)?;
+                segments(kind, "segments", ring_segments, 3)?;
+                segments(kind, "rings", rings, 2)?;
+            },
+            MeshDesc::Box { width, height, depth } => {
+                positive(kind, "width", width)?;
+                positive(kind, "height", height)?;
+                positive(kind, "depth", depth)?;
+            },
+            MeshDesc::Cylinder { radius, height, segments: ring_segments, .. } |
+            MeshDesc::Cone { radius, height, segments: ring_segments } => {
+                positive(kind, "radius", radius)?;
+                positive(kind, "height", height)?;
+                segments(kind, "segments", ring_segments, 3)?;
+            },
+            MeshDesc::Plane { width, depth, subdivisions } => {
+                positive(kind, "width", width)?;
+                positive(kind, "depth", depth)?;
+                segments(kind, "subdivisions", subdivisions, 1)?;
+            },
+            MeshDesc::Arrow { length, width, thickness } => {
+                positive(kind, "length", length)?;
+                positive(kind, "width", width)?;
+                positive(kind, "thickness", thickness)?;
+            },
+        }
+
+        let (vertices, indices) = self.size();
+
+        if vertices > MAX_VERTICES || indices > MAX_INDICES {
+            return Err(MeshError::TooLarge { mesh: kind, vertices, indices, max_vertices: MAX_VERTICES, max_indices: MAX_INDICES });
+        }
+
+        Ok(())
+    }
+
+
+
+    /// How many vertices and indices the described mesh is built with, once its parameters passed `validate`.
+    pub fn size(&self) -> (usize, usize)
+    {
+        let (vertices, indices) = match *self
+        {
+            MeshDesc::Donut { segments_major, segments_minor, .. } => {
+                let quads = segments_major * segments_minor;
+                (quads, quads * 6)
+            },
+            MeshDesc::Washer { segments, .. } => (segments * 8, segments * 8 * 6),
+            MeshDesc::Stick { segments, height_segments, top_rings, .. } => {
+                let rings = height_segments + 1 + top_rings;
+                (rings * (segments + 1) + 1, (rings - 1) * segments * 6 + segments * 3)
+            },
+            MeshDesc::Sphere { segments, rings, .. } => ((rings + 1) * (segments + 1), rings * segments * 6),
+            MeshDesc::Box { .. } => (8, 36),
+            MeshDesc::Cylinder { segments, caps, .. } => (segments * 2 + 2, segments * if caps { 12 } else { 6 }),
+            MeshDesc::Cone { segments, .. } => (segments + 2, segments * 6),
+            MeshDesc::Plane { subdivisions, .. } => ((subdivisions + 1) * (subdivisions + 1), subdivisions * subdivisions * 6),
+            MeshDesc::Arrow { .. } => (14, 60),
+        };
+
+        (vertices as usize, indices as usize)
+    }
+
+
+
+    /// The name the description starts with when written as text, like "donut".
+    pub fn kind(&self) -> &'static str
+    {
+        self.fields().0
+    }
+
+
+
+    /// Coarser version of the mesh for drawing it small, every level halves the segments.
+    /// Level 0 is the mesh itself, meshes without segments stay the same at every level.
+    pub fn lod(&self, level: u32) -> MeshDesc
+    {
+        let reduce = |value: &mut isize, min: isize| *value = (*value >> level.min(31)).max(min).min(*value);
+        let mut desc = *self;
+
+        match &mut desc
+        {
+            MeshDesc::Donut { segments_major, segments_minor, .. } => {
+                reduce(segments_major, 3);
+                reduce(segments_minor, 3);
+            },
+            MeshDesc::Stick { segments, height_segments, top_rings, .. } => {
+                reduce(segments, 3);
+                reduce(height_segments, 1);
+                reduce(top_rings, 1);
+            },
+            MeshDesc::Sphere { segments, rings, .. } => {
+                reduce(segments, 3);
+                reduce(rings, 2);
+            },
+            MeshDesc::Washer { segments, .. } |
+            MeshDesc::Cylinder { segments, .. } |
+            MeshDesc::Cone { segments, .. } => reduce(segments, 3),
+            MeshDesc::Plane { subdivisions, .. } => reduce(subdivisions, 1),
+            MeshDesc::Box { .. } | MeshDesc::Arrow { .. } => (),
+        }
+
+        desc
+    }
+
+
+
+    // The kind and the named parameters, in the order they are written out.
+    fn fields(&self) -> (&'static str, Vec<(&'static str, String)>)
+    {
+        match *self
+        {
+            MeshDesc::Donut { major_radius, minor_radius, segments_major, segments_minor } => {
+                ("donut", vec![ ("major_radius", major_radius.to_string()),
+                                ("minor_radius", minor_radius.to_string()),
+                                ("segments_major", segments_major.to_string()),
+                                ("segments_minor", segments_minor.to_string()) ])
+            },
+            MeshDesc::Washer { outer_radius, inner_radius, thickness, bevel, segments } => {
+                ("washer", vec![ ("outer_radius", outer_radius.to_string()),
+                                 ("inner_radius", inner_radius.to_string()),
+                                 ("thickness", thickness.to_string()),
+                                 ("bevel", bevel.to_string()),
+                                 ("segments", segments.to_string()) ])
+            },
+            MeshDesc::Stick { radius, height, segments, height_segments, top_rings } => {
+                ("stick", vec![ ("radius", radius.to_string()),
+                                ("height", height.to_string()),
+                                ("segments", segments.to_string()),
+                                ("height_segments", height_segments.to_string()),
+                                ("top_rings", top_rings.to_string()) ])
+            },
+            MeshDesc::Sphere { radius, segments, rings } => {
+                ("sphere", vec![ ("radius", radius.to_string()),
+                                 ("segments", segments.to_string()),
+                                 ("rings", rings.to_string()) ])
+            },
+            MeshDesc::Box { width, height, depth } => {
+                ("box", vec![ ("width", width.to_string()),
+                              ("height", height.to_string()),
+                              ("depth", depth.to_string()) ])
+            },
+            MeshDesc::Cylinder { radius, height, segments, caps } => {
+                ("cylinder", vec![ ("radius", radius.to_string()),
+                                   ("height", height.to_string()),
+                                   ("segments", segments.to_string()),
+                                   ("caps", caps.to_string()) ])
+            },
+            MeshDesc::Cone { radius, height, segments } => {
+                ("cone", vec![ ("radius", radius.to_string()),
+                               ("height", height.to_string()),
+                               ("segments", segments.to_string()) ])
+            },
+            MeshDesc::Plane { width, depth, subdivisions } => {
+                ("plane", vec![ ("width", width.to_string()),
+                                ("depth", depth.to_string()),
+                                ("subdivisions", subdivisions.to_string()) ])
+            },
+            MeshDesc::Arrow { length, width, thickness } => {
+                ("arrow", vec![ ("length", length.to_string()),
+                                ("width", width.to_string()),
+                                ("thickness", thickness.to_string()) ])
+            },
+        }
+    }
+}
+
+
+
+impl std::fmt::Display for MeshDesc
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let (kind, fields) = self.fields();
+
+        write!(f, "{}", kind)?;
+
+        for (name, value) in fields
+        {
+            write!(f, " {}={}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+
+// Named parameters of a description being parsed, every one has to be used exactly once.
+struct Fields<'a>
+{
+    values: HashMap<&'a str, &'a str>,
+}
+
+
+
+impl<'a> Fields<'a>
+{
+    fn take<T: std::str::FromStr>(&mut self, name: &str) -> Result<T, String>
+    {
+        let value = self.values.remove(name).ok_or_else(|| format!("Missing mesh parameter {:?}", name))?;
+
+        value.parse().map_err(|_| format!("Invalid value {:?} of mesh parameter {:?}", value, name))
+    }
+
+
+
+    fn finish(self, desc: MeshDesc) -> Result<MeshDesc, String>
+    {
+        match self.values.keys().next() {
+            Some(name) => Err(format!("Unknown mesh parameter {:?}", name)),
+            None => Ok(desc),
+        }
+    }
+}
+
+
+
+impl std::str::FromStr for MeshDesc
+{
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err>
+    {
+        let mut words = text.split_whitespace();
+        let kind = words.next().ok_or("Empty mesh description")?;
+        let mut values = HashMap::new();
+
+        for word in words
+        {
+            let (name, value) = word.split_once('=').ok_or_else(|| format!("Expected name=value, found {:?}", word))?;
+
+            if values.insert(name, value).is_some() {
+                return Err(format!("Mesh parameter {:?} is given twice", name));
+            }
+        }
+
+        let mut fields = Fields { values };
+
+        let desc = match kind
+        {
+            "donut" => MeshDesc::Donut {
+                major_radius: fields.take("major_radius")?,
+                minor_radius: fields.take("minor_radius")?,
+                segments_major: fields.take("segments_major")?,
+                segments_minor: fields.take("segments_minor")?,
+            },
+            "washer" => MeshDesc::Washer {
+                outer_radius: fields.take("outer_radius")?,
+                inner_radius: fields.take("inner_radius")?,
+                thickness: fields.take("thickness")?,
+                bevel: fields.take("bevel")?,
+                segments: fields.take("segments")?,
+            },
+            "stick" => MeshDesc::Stick {
+                radius: fields.take("radius")?,
+                height: fields.take("height")?,
+                segments: fields.take("segments")?,
+                height_segments: fields.take("height_segments")?,
+                top_rings: fields.take("top_rings")?,
+            },
+            "sphere" => MeshDesc::Sphere {
+                radius: fields.take("radius")?,
+                segments: fields.take("segments")?,
+                rings: fields.take("rings")?,
+            },
+            "box" => MeshDesc::Box {
+                width: fields.take("width")?,
+                height: fields.take("height")?,
+                depth: fields.take("depth")?,
+            },
+            "cylinder" => MeshDesc::Cylinder {
+                radius: fields.take("radius")?,
+                height: fields.take("height")?,
+                segments: fields.take("segments")?,
+                caps: fields.take("caps")?,
+            },
+            "cone" => MeshDesc::Cone {
+                radius: fields.take("radius")?,
+                height: fields.take("height")?,
+                segments: fields.take("segments")?,
+            },
+            "plane" => MeshDesc::Plane {
+                width: fields.take("width")?,
+                depth: fields.take("depth")?,
+                subdivisions: fields.take("subdivisions")?,
+            },
+            "arrow" => MeshDesc::Arrow {
+                length: fields.take("length")?,
+                width: fields.take("width")?,
+                thickness: fields.take("thickness")?,
+            },
+            _ => return Err(format!("Unknown mesh kind {:?}", kind)),
+        };
+
+        fields.finish(desc)
+    }
+}
@@ -0,0 +1,56 @@
+#![allow(dead_code)]
+
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum MeshError
+{
+    // A descriptor parameter outside of what the factory can build, e.g. a negative radius.
+    InvalidParameter { mesh: &'static str, parameter: &'static str, value: f32, expected: &'static str },
+    EmptyMesh,
+    // The index buffer isn't a list of whole triangles.
+    IncompleteTriangle { indices: usize },
+    IndexOutOfRange { index: u32, vertices: usize },
+    TooManyVertices { vertices: usize },
+    // The parameters are fine one by one, but together they ask for too big a mesh.
+    TooLarge { mesh: &'static str, vertices: usize, indices: usize, max_vertices: usize, max_indices: usize },
+    // Reading or writing a mesh file failed.
+    Io { path: String, message: String },
+    // A model file that can't be read as a mesh.
+    InvalidModel { path: String, message: String },
+}
+
+
+
+impl std::fmt::Display for MeshError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            MeshError::InvalidParameter { mesh, parameter, value, expected } => {
+                write!(f, "{} {} is {}, expected {}", mesh, parameter, value, expected)
+            },
+            MeshError::EmptyMesh => write!(f, "the mesh has no triangles"),
+            MeshError::IncompleteTriangle { indices } => {
+                write!(f, "{} indices don't make whole triangles", indices)
+            },
+            MeshError::IndexOutOfRange { index, vertices } => {
+                write!(f, "index {} points past the {} vertices", index, vertices)
+            },
+            MeshError::TooManyVertices { vertices } => {
+                write!(f, "{} vertices don't fit into 32 bit indices", vertices)
+            },
+            MeshError::TooLarge { mesh, vertices, indices, max_vertices, max_indices } => {
+                write!(f,
+                       "{} would have {} vertices and {} indices, at most {} and {} are allowed",
+                       mesh, vertices, indices, max_vertices, max_indices)
+            },
+            MeshError::Io { path, message } => write!(f, "{}: {}", path, message),
+            MeshError::InvalidModel { path, message } => write!(f, "{} isn't a usable model: {}", path, message),
+        }
+    }
+}
+
+
+
+impl std::error::Error for MeshError {}
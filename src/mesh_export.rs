@@ -0,0 +1,355 @@
+#![allow(dead_code)]
+
+use std::rc::Rc;
+
+use vecmath::Matrix4;
+
+use crate::{
+    base64,
+    base_mesh_trait::MeshData,
+    master_trait::Master,
+    mesh_cache::MeshCache,
+    mesh_error::MeshError,
+};
+
+
+// glTF accessor and buffer view constants.
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const TRIANGLES: u32 = 4;
+
+
+
+/// One actor of an exported scene, actors drawn with the same mesh share `mesh`.
+#[derive(Clone, Debug)]
+pub struct ExportedMesh
+{
+    pub name: String,
+    pub mesh: Rc<MeshData>,
+    pub model: Matrix4<f32>,
+    pub color: [f32; 4],
+}
+
+
+
+/// Every actor of the master with a mesh, with its current transform.
+pub fn collect_scene(master: &dyn Master, cache: &MeshCache) -> Vec<ExportedMesh>
+{
+    let mut scene = Vec::new();
+
+    master.visit_actors(&mut |base| {
+        let Some(mesh) = base.mesh else {
+            return;
+        };
+        let mesh = cache.get(mesh);
+
+        // Widened rings are written out the way they are drawn.
+        let data = match base.ring_growth != 0. {
+            true => Rc::new(mesh.data.grown(base.ring_growth)),
+            false => mesh.data.clone(),
+        };
+
+        scene.push(ExportedMesh {
+            name: mesh.name.clone(),
+            mesh: data,
+            model: base.get_model(),
+            color: base.color,
+        });
+    });
+
+    scene
+}
+
+
+
+/// Writes the scene next to each other as `<path>.obj` and `<path>.gltf`.
+pub fn write_scene(scene: &[ExportedMesh], path: &str) -> Result<(), MeshError>
+{
+    write_file(&format!("{}.obj", path), &scene_to_obj(scene))?;
+    write_file(&format!("{}.gltf", path), &scene_to_gltf(scene))
+}
+
+
+
+fn write_file(path: &str, text: &str) -> Result<(), MeshError>
+{
+    std::fs::write(path, text).map_err(|error| MeshError::Io { path: path.to_string(), message: error.to_string() })
+}
+
+
+
+/// Wavefront OBJ of one mesh in its own coordinates.
+pub fn mesh_to_obj(mesh: &MeshData, name: &str) -> String
+{
+    let mut obj = String::new();
+    write_obj_object(&mut obj, name, mesh, vecmath::mat4_id(), None, 1);
+
+    obj
+}
+
+
+
+/// The meshes are moved into world space, the actor colours are written as vertex colours.
+pub fn scene_to_obj(scene: &[ExportedMesh]) -> String
+{
+    let mut obj = String::new();
+    let mut first_vertex = 1;
+
+    for (i, actor) in scene.iter().enumerate()
+    {
+        write_obj_object(&mut obj, &format!("actor_{}", i), &actor.mesh, actor.model, Some(actor.color), first_vertex);
+        first_vertex += actor.mesh.vertices.len();
+    }
+
+    obj
+}
+
+
+
+// OBJ indices are global to the file and count from 1.
+fn write_obj_object(obj:          &mut String,
+                    name:         &str,
+                    mesh:         &MeshData,
+                    model:        Matrix4<f32>,
+                    color:        Option<[f32; 4]>,
+                    first_vertex: usize)
+{
+    obj.push_str(&format!("o {}\n", name));
+
+    for vertex in mesh.vertices.iter()
+    {
+        let [x, y, z, _] = vecmath::col_mat4_transform(model, vertex.a_pos);
+
+        match color
+        {
+            Some([r, g, b, _]) => obj.push_str(&format!("v {} {} {} {} {} {}\n", x, y, z, r, g, b)),
+            None => obj.push_str(&format!("v {} {} {}\n", x, y, z)),
+        }
+    }
+
+    for triangle in mesh.indices.chunks(3)
+    {
+        let [a, b, c] = [ 0, 1, 2 ].map(|i| triangle[i] as usize + first_vertex);
+        obj.push_str(&format!("f {} {} {}\n", a, b, c));
+    }
+}
+
+
+
+/// glTF 2.0 of one mesh with the buffer embedded, so the result is a single file.
+pub fn mesh_to_gltf(mesh: &MeshData, name: &str) -> String
+{
+    let mut gltf = GltfBuilder::new();
+
+    let geometry = gltf.add_geometry(mesh);
+    let mesh = gltf.add_mesh(name, geometry, None);
+    gltf.add_node(mesh, None);
+
+    gltf.finish()
+}
+
+
+
+/// Actors drawn with the same mesh share their geometry, every actor is a node with its transform.
+pub fn scene_to_gltf(scene: &[ExportedMesh]) -> String
+{
+    let mut gltf = GltfBuilder::new();
+    let mut geometries: Vec<(Rc<MeshData>, (usize, usize))> = Vec::new();
+    let mut materials: Vec<([f32; 4], usize)> = Vec::new();
+    let mut meshes: Vec<((usize, usize), usize)> = Vec::new();
+
+    for actor in scene
+    {
+        let geometry = match geometries.iter().find(|(mesh, _)| Rc::ptr_eq(mesh, &actor.mesh)) {
+            Some((_, geometry)) => *geometry,
+            None => {
+                let geometry = gltf.add_geometry(&actor.mesh);
+                geometries.push((actor.mesh.clone(), geometry));
+                geometry
+            },
+        };
+
+        let material = match materials.iter().find(|(color, _)| *color == actor.color) {
+            Some((_, material)) => *material,
+            None => {
+                let material = gltf.add_material(actor.color);
+                materials.push((actor.color, material));
+                material
+            },
+        };
+
+        let mesh = match meshes.iter().find(|(key, _)| *key == (geometry.0, material)) {
+            Some((_, mesh)) => *mesh,
+            None => {
+                let mesh = gltf.add_mesh(&actor.name, geometry, Some(material));
+                meshes.push(((geometry.0, material), mesh));
+                mesh
+            },
+        };
+
+        gltf.add_node(mesh, Some(actor.model));
+    }
+
+    gltf.finish()
+}
+
+
+
+// Collects the JSON objects of every glTF array, each one already written out.
+struct GltfBuilder
+{
+    buffer: Vec<u8>,
+    buffer_views: Vec<String>,
+    accessors: Vec<String>,
+    materials: Vec<String>,
+    meshes: Vec<String>,
+    nodes: Vec<String>,
+}
+
+
+
+impl GltfBuilder
+{
+    fn new() -> Self
+    {
+        GltfBuilder {
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            materials: Vec::new(),
+            meshes: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+
+
+    fn add_buffer_view(&mut self, bytes: &[u8], target: u32) -> usize
+    {
+        self.buffer_views.push(format!("{{ \"buffer\": 0, \"byteOffset\": {}, \"byteLength\": {}, \"target\": {} }}",
+                                       self.buffer.len(),
+                                       bytes.len(),
+                                       target));
+        self.buffer.extend_from_slice(bytes);
+
+        self.buffer_views.len() - 1
+    }
+
+
+
+    // The accessors of the positions and of the indices.
+    fn add_geometry(&mut self, mesh: &MeshData) -> (usize, usize)
+    {
+        let positions: Vec<[f32; 3]> = mesh.vertices.iter().map(|vertex| [ vertex.a_pos[0], vertex.a_pos[1], vertex.a_pos[2] ]).collect();
+        let (min, max) = mesh.bounds();
+
+        let bytes: Vec<u8> = positions.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+        let view = self.add_buffer_view(&bytes, ARRAY_BUFFER);
+        self.accessors.push(format!("{{ \"bufferView\": {}, \"componentType\": {}, \"count\": {}, \"type\": \"VEC3\", \"min\": {}, \"max\": {} }}",
+                                    view,
+                                    FLOAT,
+                                    positions.len(),
+                                    json_floats(&min),
+                                    json_floats(&max)));
+        let position_accessor = self.accessors.len() - 1;
+
+        let bytes: Vec<u8> = mesh.indices.iter().flat_map(|index| index.to_le_bytes()).collect();
+        let view = self.add_buffer_view(&bytes, ELEMENT_ARRAY_BUFFER);
+        self.accessors.push(format!("{{ \"bufferView\": {}, \"componentType\": {}, \"count\": {}, \"type\": \"SCALAR\" }}",
+                                    view,
+                                    UNSIGNED_INT,
+                                    mesh.indices.len()));
+
+        (position_accessor, self.accessors.len() - 1)
+    }
+
+
+
+    fn add_material(&mut self, color: [f32; 4]) -> usize
+    {
+        self.materials.push(format!("{{ \"pbrMetallicRoughness\": {{ \"baseColorFactor\": {}, \"metallicFactor\": 0, \"roughnessFactor\": 0.5 }} }}",
+                                    json_floats(&color)));
+
+        self.materials.len() - 1
+    }
+
+
+
+    fn add_mesh(&mut self, name: &str, geometry: (usize, usize), material: Option<usize>) -> usize
+    {
+        let material = material.map_or(String::new(), |material| format!(", \"material\": {}", material));
+
+        self.meshes.push(format!("{{ \"name\": \"{}\", \"primitives\": [ {{ \"attributes\": {{ \"POSITION\": {} }}, \"indices\": {}{}, \"mode\": {} }} ] }}",
+                                 json_escape(name),
+                                 geometry.0,
+                                 geometry.1,
+                                 material,
+                                 TRIANGLES));
+
+        self.meshes.len() - 1
+    }
+
+
+
+    fn add_node(&mut self, mesh: usize, model: Option<Matrix4<f32>>)
+    {
+        // glTF matrices are column-major like vecmath ones.
+        let matrix = model.map_or(String::new(), |model| format!(", \"matrix\": {}", json_floats(model.as_flattened())));
+
+        self.nodes.push(format!("{{ \"mesh\": {}{} }}", mesh, matrix));
+    }
+
+
+
+    fn finish(self) -> String
+    {
+        let nodes: Vec<String> = (0..self.nodes.len()).map(|node| node.to_string()).collect();
+
+        let mut json = String::from("{\n");
+        json.push_str("  \"asset\": { \"version\": \"2.0\", \"generator\": \"kolo\" },\n");
+        json.push_str("  \"scene\": 0,\n");
+        json.push_str(&format!("  \"scenes\": [ {{ \"nodes\": [ {} ] }} ],\n", nodes.join(", ")));
+        json.push_str(&json_array("nodes", &self.nodes));
+        json.push_str(&json_array("meshes", &self.meshes));
+
+        if !self.materials.is_empty() {
+            json.push_str(&json_array("materials", &self.materials));
+        }
+
+        json.push_str(&json_array("accessors", &self.accessors));
+        json.push_str(&json_array("bufferViews", &self.buffer_views));
+        json.push_str(&format!("  \"buffers\": [ {{ \"byteLength\": {}, \"uri\": \"data:application/octet-stream;base64,{}\" }} ]\n",
+                               self.buffer.len(),
+                               base64::encode(&self.buffer)));
+        json.push_str("}\n");
+
+        json
+    }
+}
+
+
+
+// One element per line, so exported scenes diff line by line.
+fn json_array(name: &str, elements: &[String]) -> String
+{
+    format!("  \"{}\": [\n    {}\n  ],\n", name, elements.join(",\n    "))
+}
+
+
+
+fn json_floats(values: &[f32]) -> String
+{
+    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+
+    format!("[ {} ]", values.join(", "))
+}
+
+
+
+fn json_escape(text: &str) -> String
+{
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
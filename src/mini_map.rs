@@ -0,0 +1,73 @@
+#![allow(dead_code)]
+
+use crate::{
+    actor_trait::{Actor, ActorBase},
+    scene_graph::{self, SceneNode},
+    stick_actor::AStick,
+    tower_state::Peg,
+};
+
+
+const MINI_MAP_SCALE: f32 = 0.3;
+
+
+/// A shrunk copy of the sticks showing the layout to reach, the pieces on it are placed
+/// relative to the foot of their peg and kept by the master.
+pub struct MiniMap
+{
+    node: SceneNode,
+    pegs: Vec<SceneNode>,
+    sticks: Vec::<AStick>,
+}
+
+
+
+impl MiniMap
+{
+    pub fn new(position: vecmath::Vector3<f32>) -> Self
+    {
+        let node = scene_graph::group(position);
+        node.borrow_mut().set_scale([ MINI_MAP_SCALE, MINI_MAP_SCALE, MINI_MAP_SCALE ]);
+
+        MiniMap {
+            node,
+            pegs: Vec::new(),
+            sticks: Vec::new(),
+        }
+    }
+
+
+
+    /// The next peg with its foot `x` along the map, every peg stands on one line.
+    pub fn add_peg(&mut self, x: f32, stick: AStick)
+    {
+        let peg_node = scene_graph::group([ x, 0., 0. ]);
+        scene_graph::attach(&self.node, &peg_node);
+        scene_graph::attach(&peg_node, &stick.actor_base);
+
+        self.pegs.push(peg_node);
+        self.sticks.push(stick);
+    }
+
+
+
+    pub fn place(&self, peg: Peg, piece: &SceneNode, position: vecmath::Vector3<f32>)
+    {
+        scene_graph::attach(&self.pegs[peg], piece);
+        piece.borrow_mut().set_position(position);
+    }
+
+
+
+    pub fn update(&mut self)
+    {
+        self.sticks.iter_mut().for_each(|actor| actor.update());
+    }
+
+
+
+    pub fn visit_actors(&self, visit: &mut dyn FnMut(&ActorBase))
+    {
+        self.sticks.iter().for_each(|actor| visit(&actor.actor_base.borrow()));
+    }
+}
@@ -0,0 +1,432 @@
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use vecmath::Matrix4;
+
+use crate::{
+    base64,
+    base_mesh_trait::{MeshData, Vertex},
+    json::Json,
+    mesh_error::MeshError,
+};
+
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+
+// glTF accessor component types and the triangle list primitive mode.
+const UNSIGNED_BYTE: u64 = 5121;
+const UNSIGNED_SHORT: u64 = 5123;
+const UNSIGNED_INT: u64 = 5125;
+const FLOAT: u64 = 5126;
+const TRIANGLES: usize = 4;
+
+// Deeper node trees are taken for a loop.
+const MAX_NODE_DEPTH: u32 = 64;
+
+
+
+fn longest_side((min, max): ([f32; 3], [f32; 3])) -> f32
+{
+    (0..3).map(|axis| max[axis] - min[axis]).fold(0., f32::max)
+}
+
+
+
+/// `model` moved onto the centre of `target` and uniformly scaled to the longest side of `target`,
+/// so a model stands in for a procedural mesh of any size.
+pub fn fit_model(model: &MeshData, target: &MeshData) -> MeshData
+{
+    let (model_min, model_max) = model.bounds();
+    let (target_min, target_max) = target.bounds();
+    let scale = longest_side(target.bounds()) / longest_side(model.bounds());
+
+    let vertices = model.vertices
+                        .iter()
+                        .map(|vertex| {
+                            let mut pos = [ 0.; 3 ];
+
+                            for axis in 0..3
+                            {
+                                let model_center = (model_min[axis] + model_max[axis]) * 0.5;
+                                let target_center = (target_min[axis] + target_max[axis]) * 0.5;
+
+                                pos[axis] = (vertex.a_pos[axis] - model_center) * scale + target_center;
+                            }
+
+                            // A uniform scale keeps the directions of the normals.
+                            Vertex { a_pos: [ pos[0], pos[1], pos[2], 1. ], ..*vertex }
+                        })
+                        .collect();
+
+    MeshData {
+        vertices,
+        indices: model.indices.clone(),
+    }
+}
+
+
+
+/// Reads the triangles of a model file, the format comes from the extension.
+pub fn load_model(path: &str) -> Result<MeshData, MeshError>
+{
+    let bytes = std::fs::read(path).map_err(|error| MeshError::Io { path: path.to_string(), message: error.to_string() })?;
+    let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+    let extension = Path::new(path).extension()
+                                   .and_then(|extension| extension.to_str())
+                                   .unwrap_or("")
+                                   .to_ascii_lowercase();
+
+    let triangles = match extension.as_str()
+    {
+        "obj" => parse_obj(&String::from_utf8_lossy(&bytes)),
+        "gltf" => std::str::from_utf8(&bytes).map_err(|_| "the file isn't UTF-8 text".to_string())
+                                             .and_then(Json::parse)
+                                             .and_then(|json| parse_gltf(&json, None, directory)),
+        "glb" => parse_glb(&bytes, directory),
+        _ => Err(format!("unknown format {:?}, expected obj, gltf or glb", extension)),
+    };
+
+    let invalid = |message: String| MeshError::InvalidModel { path: path.to_string(), message };
+    let (vertices, indices) = triangles.map_err(invalid)?;
+    let mesh = MeshData::new(vertices, indices)?;
+
+    match longest_side(mesh.bounds()) {
+        side if side > 0. && side.is_finite() => Ok(mesh),
+        _ => Err(invalid("the model has no size".to_string())),
+    }
+}
+
+
+
+// Polygons are split into triangle fans, texture coordinates, normals and materials are skipped.
+fn parse_obj(text: &str) -> Result<(Vec<Vertex>, Vec<u32>), String>
+{
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for (number, line) in text.lines().enumerate()
+    {
+        let mut words = line.split_whitespace();
+        let error = |what: &str| format!("line {}: {}", number + 1, what);
+
+        match words.next()
+        {
+            Some("v") => {
+                let pos: Vec<f32> = words.take(3)
+                                         .map(|word| word.parse().ok())
+                                         .collect::<Option<_>>()
+                                         .ok_or_else(|| error("invalid vertex"))?;
+
+                match pos.as_slice() {
+                    [x, y, z] => vertices.push(Vertex::new([*x, *y, *z])),
+                    _ => return Err(error("a vertex needs three coordinates")),
+                }
+            },
+            Some("f") => {
+                let corners: Vec<u32> = words.map(|word| obj_index(word, vertices.len()))
+                                             .collect::<Option<_>>()
+                                             .ok_or_else(|| error("invalid face"))?;
+
+                if corners.len() < 3 {
+                    return Err(error("a face needs at least three corners"));
+                }
+
+                for i in 1..corners.len() - 1
+                {
+                    indices.extend_from_slice(&[ corners[0], corners[i], corners[i + 1] ]);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    Ok((vertices, indices))
+}
+
+
+
+// "7", "7/1" or "7/1/3" count from 1, negative indices count back from the last vertex.
+fn obj_index(word: &str, vertices: usize) -> Option<u32>
+{
+    let index: i64 = word.split('/').next()?.parse().ok()?;
+
+    let index = match index {
+        0 => return None,
+        index if index < 0 => vertices as i64 + index,
+        index => index - 1,
+    };
+
+    u32::try_from(index).ok()
+}
+
+
+
+fn parse_glb(bytes: &[u8], directory: &Path) -> Result<(Vec<Vertex>, Vec<u32>), String>
+{
+    let word = |at: usize| bytes.get(at..at + 4).map(|word| u32::from_le_bytes([ word[0], word[1], word[2], word[3] ]));
+
+    if word(0) != Some(GLB_MAGIC) {
+        return Err("the file doesn't start with the glTF magic".to_string());
+    }
+
+    let mut json = None;
+    let mut binary = None;
+    let mut at = 12;
+
+    while let (Some(length), Some(kind)) = (word(at), word(at + 4))
+    {
+        let chunk = bytes.get(at + 8..at + 8 + length as usize).ok_or("a chunk is cut short")?;
+
+        match kind {
+            GLB_JSON_CHUNK => json = Some(chunk),
+            GLB_BIN_CHUNK => binary = Some(chunk),
+            _ => (),
+        }
+
+        at += 8 + length as usize;
+    }
+
+    let json = json.ok_or("the JSON chunk is missing")?;
+    let json = std::str::from_utf8(json).map_err(|_| "the JSON chunk isn't UTF-8".to_string())
+                                        .and_then(Json::parse)?;
+
+    parse_gltf(&json, binary, directory)
+}
+
+
+
+struct Gltf<'a>
+{
+    json: &'a Json,
+    buffers: Vec<Vec<u8>>,
+}
+
+
+
+// Takes the meshes placed by the nodes of the default scene, or every mesh as it is without scenes.
+fn parse_gltf(json: &Json, binary: Option<&[u8]>, directory: &Path) -> Result<(Vec<Vertex>, Vec<u32>), String>
+{
+    let mut buffers = Vec::new();
+
+    for buffer in json.get("buffers").and_then(Json::as_array).unwrap_or(&[])
+    {
+        let data = match buffer.get("uri").and_then(Json::as_str)
+        {
+            Some(uri) if uri.starts_with("data:") => {
+                uri.split_once(',').and_then(|(_, data)| base64::decode(data)).ok_or("invalid data URI")?
+            },
+            Some(uri) => {
+                std::fs::read(directory.join(uri)).map_err(|error| format!("{}: {}", uri, error))?
+            },
+            None => binary.ok_or("a buffer without an URI needs the GLB binary chunk")?.to_vec(),
+        };
+
+        buffers.push(data);
+    }
+
+    let gltf = Gltf { json, buffers };
+    let mut triangles = (Vec::new(), Vec::new());
+
+    let scene = json.get("scene").and_then(Json::as_usize).unwrap_or(0);
+    let roots = json.get("scenes").and_then(|scenes| scenes.at(scene)).and_then(|scene| scene.get("nodes"));
+
+    match roots.and_then(Json::as_array)
+    {
+        Some(roots) => {
+            for root in roots
+            {
+                let root = root.as_usize().ok_or("invalid node index")?;
+                add_node(&gltf, root, vecmath::mat4_id(), 0, &mut triangles)?;
+            }
+        },
+        None => {
+            for mesh in 0..json.get("meshes").and_then(Json::as_array).map_or(0, |meshes| meshes.len())
+            {
+                add_mesh(&gltf, mesh, vecmath::mat4_id(), &mut triangles)?;
+            }
+        },
+    }
+
+    Ok(triangles)
+}
+
+
+
+fn add_node(gltf:      &Gltf,
+            node:      usize,
+            parent:    Matrix4<f32>,
+            depth:     u32,
+            triangles: &mut (Vec<Vertex>, Vec<u32>)) -> Result<(), String>
+{
+    if depth > MAX_NODE_DEPTH {
+        return Err("the nodes form a loop".to_string());
+    }
+
+    let json = gltf.json.get("nodes").and_then(|nodes| nodes.at(node)).ok_or("missing node")?;
+    let transform = vecmath::col_mat4_mul(parent, node_matrix(json)?);
+
+    if let Some(mesh) = json.get("mesh").and_then(Json::as_usize) {
+        add_mesh(gltf, mesh, transform, triangles)?;
+    }
+
+    for child in json.get("children").and_then(Json::as_array).unwrap_or(&[])
+    {
+        add_node(gltf, child.as_usize().ok_or("invalid node index")?, transform, depth + 1, triangles)?;
+    }
+
+    Ok(())
+}
+
+
+
+fn floats<const N: usize>(json: Option<&Json>, default: [f32; N]) -> Result<[f32; N], String>
+{
+    let Some(json) = json else {
+        return Ok(default);
+    };
+
+    let values: Vec<f32> = json.as_array()
+                               .and_then(|values| values.iter().map(|value| value.as_f64().map(|value| value as f32)).collect())
+                               .ok_or("invalid node transform")?;
+
+    values.try_into().map_err(|_| "invalid node transform".to_string())
+}
+
+
+
+// Either the column-major "matrix" or translation * rotation * scale.
+fn node_matrix(node: &Json) -> Result<Matrix4<f32>, String>
+{
+    if node.get("matrix").is_some() {
+        let m: [f32; 16] = floats(node.get("matrix"), [ 0.; 16 ])?;
+
+        return Ok([ [ m[0], m[1], m[2], m[3] ],
+                    [ m[4], m[5], m[6], m[7] ],
+                    [ m[8], m[9], m[10], m[11] ],
+                    [ m[12], m[13], m[14], m[15] ] ]);
+    }
+
+    let [tx, ty, tz] = floats(node.get("translation"), [ 0., 0., 0. ])?;
+    let [x, y, z, w] = floats(node.get("rotation"), [ 0., 0., 0., 1. ])?;
+    let [sx, sy, sz] = floats(node.get("scale"), [ 1., 1., 1. ])?;
+
+    Ok([ [ (1. - 2. * (y * y + z * z)) * sx, 2. * (x * y + z * w) * sx, 2. * (x * z - y * w) * sx, 0. ],
+         [ 2. * (x * y - z * w) * sy, (1. - 2. * (x * x + z * z)) * sy, 2. * (y * z + x * w) * sy, 0. ],
+         [ 2. * (x * z + y * w) * sz, 2. * (y * z - x * w) * sz, (1. - 2. * (x * x + y * y)) * sz, 0. ],
+         [ tx, ty, tz, 1. ] ])
+}
+
+
+
+// Primitives other than triangle lists are skipped.
+fn add_mesh(gltf:      &Gltf,
+            mesh:      usize,
+            transform: Matrix4<f32>,
+            triangles: &mut (Vec<Vertex>, Vec<u32>)) -> Result<(), String>
+{
+    let json = gltf.json.get("meshes").and_then(|meshes| meshes.at(mesh)).ok_or("missing mesh")?;
+
+    for primitive in json.get("primitives").and_then(Json::as_array).unwrap_or(&[])
+    {
+        if primitive.get("mode").and_then(Json::as_usize).unwrap_or(TRIANGLES) != TRIANGLES {
+            continue;
+        }
+
+        let positions = primitive.get("attributes")
+                                 .and_then(|attributes| attributes.get("POSITION"))
+                                 .and_then(Json::as_usize)
+                                 .ok_or("a primitive has no positions")?;
+        let first = u32::try_from(triangles.0.len()).map_err(|_| "too many vertices")?;
+        let count = read_positions(gltf, positions, transform, &mut triangles.0)?;
+
+        match primitive.get("indices").and_then(Json::as_usize)
+        {
+            Some(indices) => {
+                let indices = read_indices(gltf, indices)?;
+                triangles.1.extend(indices.into_iter().map(|index| first + index));
+            },
+            None => triangles.1.extend((0..count as u32).map(|index| first + index)),
+        }
+    }
+
+    Ok(())
+}
+
+
+
+// The bytes of every element of an accessor, each `size` long.
+fn accessor_elements<'a>(gltf: &'a Gltf, accessor: usize, size: usize) -> Result<Vec<&'a [u8]>, String>
+{
+    let json = gltf.json.get("accessors").and_then(|accessors| accessors.at(accessor)).ok_or("missing accessor")?;
+    let view = json.get("bufferView")
+                   .and_then(Json::as_usize)
+                   .and_then(|view| gltf.json.get("bufferViews")?.at(view))
+                   .ok_or("accessors without a buffer view aren't supported")?;
+    let buffer = view.get("buffer")
+                     .and_then(Json::as_usize)
+                     .and_then(|buffer| gltf.buffers.get(buffer))
+                     .ok_or("missing buffer")?;
+
+    let count = json.get("count").and_then(Json::as_usize).ok_or("an accessor has no count")?;
+    let offset = view.get("byteOffset").and_then(Json::as_usize).unwrap_or(0)
+                     .checked_add(json.get("byteOffset").and_then(Json::as_usize).unwrap_or(0));
+    let stride = view.get("byteStride").and_then(Json::as_usize).unwrap_or(size);
+
+    // The counts and offsets of a broken file may overflow, the last element is checked before reading any.
+    let end = match count {
+        0 => offset,
+        count => (count - 1).checked_mul(stride).and_then(|last| last.checked_add(offset?)?.checked_add(size)),
+    };
+
+    match (offset, end) {
+        (Some(offset), Some(end)) if end <= buffer.len() => Ok((0..count).map(|i| &buffer[offset + i * stride..offset + i * stride + size]).collect()),
+        _ => Err("an accessor reads past its buffer".to_string()),
+    }
+}
+
+
+
+fn read_positions(gltf:      &Gltf,
+                  accessor:  usize,
+                  transform: Matrix4<f32>,
+                  vertices:  &mut Vec<Vertex>) -> Result<usize, String>
+{
+    let json = gltf.json.get("accessors").and_then(|accessors| accessors.at(accessor)).ok_or("missing accessor")?;
+
+    if json.get("componentType").and_then(Json::as_f64) != Some(FLOAT as f64) || json.get("type").and_then(Json::as_str) != Some("VEC3") {
+        return Err("positions have to be float triples".to_string());
+    }
+
+    let elements = accessor_elements(gltf, accessor, 12)?;
+
+    for element in elements.iter()
+    {
+        let coordinate = |axis: usize| f32::from_le_bytes([ element[axis * 4], element[axis * 4 + 1], element[axis * 4 + 2], element[axis * 4 + 3] ]);
+        let pos = vecmath::col_mat4_transform(transform, [ coordinate(0), coordinate(1), coordinate(2), 1. ]);
+
+        vertices.push(Vertex::new([ pos[0], pos[1], pos[2] ]));
+    }
+
+    Ok(elements.len())
+}
+
+
+
+fn read_indices(gltf: &Gltf, accessor: usize) -> Result<Vec<u32>, String>
+{
+    let json = gltf.json.get("accessors").and_then(|accessors| accessors.at(accessor)).ok_or("missing accessor")?;
+
+    let size = match json.get("componentType").and_then(Json::as_f64).map(|kind| kind as u64) {
+        Some(UNSIGNED_BYTE) => 1,
+        Some(UNSIGNED_SHORT) => 2,
+        Some(UNSIGNED_INT) => 4,
+        _ => return Err("indices have to be unsigned integers".to_string()),
+    };
+
+    Ok(accessor_elements(gltf, accessor, size)?.iter()
+                                               .map(|element| element.iter().rev().fold(0, |index, byte| index << 8 | *byte as u32))
+                                               .collect())
+}
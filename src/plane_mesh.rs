@@ -0,0 +1,91 @@
+#![allow(dead_code)]
+
+use crate::{base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// Flat grid in the XZ plane centred at the origin, facing +Y.
+pub struct PlaneMeshFactory
+{
+    pub width: f32,
+    pub depth: f32,
+    pub subdivisions: isize,
+}
+
+
+
+impl PlaneMeshFactory 
+{
+    pub fn new(width: f32, depth: f32) -> Self
+    {
+        PlaneMeshFactory {
+            width,
+            depth,
+            subdivisions: 1,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for PlaneMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Plane {
+            width: self.width,
+            depth: self.depth,
+            subdivisions: self.subdivisions,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for PlaneMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let mut vertex_data = Vec::new();
+        let mut index_data = Vec::new();
+
+        let subdivisions = self.subdivisions;
+
+        for i in 0..=subdivisions 
+        {
+            let z = (i as f32 / subdivisions as f32 - 0.5) * self.depth;
+
+            for j in 0..=subdivisions 
+            {
+                let x = (j as f32 / subdivisions as f32 - 0.5) * self.width;
+
+                vertex_data.push(base_mesh_trait::Vertex::new([x, 0., z]));
+            }
+        }
+
+        for i in 0..subdivisions 
+        {
+            for j in 0..subdivisions 
+            {
+                let row1 = i * (subdivisions + 1);
+                let row2 = (i + 1) * (subdivisions + 1);
+
+                let a = (row1 + j) as u32;
+                let b = (row2 + j) as u32;
+                let c = (row2 + j + 1) as u32;
+                let d = (row1 + j + 1) as u32;
+
+                index_data.extend_from_slice(&[a, b, c, c, d, a]);
+            }
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
@@ -0,0 +1,164 @@
+#![allow(dead_code)]
+
+use vecmath::{Matrix4, Vector3};
+
+
+// Rotations closer than this are blended linearly, slerp divides by the sine of the angle between them.
+const SLERP_LINEAR_DOT: f32 = 0.9995;
+
+
+
+/// Rotation as a unit quaternion, angles turn counter-clockwise looking down the axis.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Quaternion
+{
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+
+
+impl Quaternion
+{
+    pub fn identity() -> Self
+    {
+        Quaternion { w: 1., x: 0., y: 0., z: 0. }
+    }
+
+
+
+    /// `axis` doesn't have to be normalized.
+    pub fn from_axis_angle(axis: Vector3<f32>, angle: f32) -> Self
+    {
+        let axis = vecmath::vec3_normalized(axis);
+        let (sin, cos) = (angle * 0.5).sin_cos();
+
+        Quaternion { w: cos, x: axis[0] * sin, y: axis[1] * sin, z: axis[2] * sin }
+    }
+
+
+
+    /// The rotation of a matrix whose first three columns are orthonormal axes.
+    pub fn from_matrix(m: &Matrix4<f32>) -> Self
+    {
+        // Row and column, the matrix is stored by columns.
+        let r = |row: usize, column: usize| m[column][row];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+
+        let q = if trace > 0. {
+            let s = (trace + 1.).sqrt() * 2.;
+            Quaternion { w: 0.25 * s, x: (r(2, 1) - r(1, 2)) / s, y: (r(0, 2) - r(2, 0)) / s, z: (r(1, 0) - r(0, 1)) / s }
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = (1. + r(0, 0) - r(1, 1) - r(2, 2)).sqrt() * 2.;
+            Quaternion { w: (r(2, 1) - r(1, 2)) / s, x: 0.25 * s, y: (r(0, 1) + r(1, 0)) / s, z: (r(0, 2) + r(2, 0)) / s }
+        } else if r(1, 1) > r(2, 2) {
+            let s = (1. + r(1, 1) - r(0, 0) - r(2, 2)).sqrt() * 2.;
+            Quaternion { w: (r(0, 2) - r(2, 0)) / s, x: (r(0, 1) + r(1, 0)) / s, y: 0.25 * s, z: (r(1, 2) + r(2, 1)) / s }
+        } else {
+            let s = (1. + r(2, 2) - r(0, 0) - r(1, 1)).sqrt() * 2.;
+            Quaternion { w: (r(1, 0) - r(0, 1)) / s, x: (r(0, 2) + r(2, 0)) / s, y: (r(1, 2) + r(2, 1)) / s, z: 0.25 * s }
+        };
+
+        q.normalized()
+    }
+
+
+
+    pub fn dot(&self, other: &Quaternion) -> f32
+    {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+
+
+    pub fn normalized(&self) -> Self
+    {
+        let length = self.dot(self).sqrt();
+
+        Quaternion { w: self.w / length, x: self.x / length, y: self.y / length, z: self.z / length }
+    }
+
+
+
+    /// The opposite rotation, for unit quaternions.
+    pub fn conjugate(&self) -> Self
+    {
+        Quaternion { w: self.w, x: -self.x, y: -self.y, z: -self.z }
+    }
+
+
+
+    pub fn rotate(&self, v: Vector3<f32>) -> Vector3<f32>
+    {
+        let u = [ self.x, self.y, self.z ];
+        let uv = vecmath::vec3_cross(u, v);
+        let uuv = vecmath::vec3_cross(u, uv);
+
+        vecmath::vec3_add(v, vecmath::vec3_add(vecmath::vec3_scale(uv, 2. * self.w), vecmath::vec3_scale(uuv, 2.)))
+    }
+
+
+
+    pub fn to_matrix(&self) -> Matrix4<f32>
+    {
+        let Quaternion { w, x, y, z } = *self;
+
+        [ [ 1. - 2. * (y * y + z * z), 2. * (x * y + w * z), 2. * (x * z - w * y), 0. ],
+          [ 2. * (x * y - w * z), 1. - 2. * (x * x + z * z), 2. * (y * z + w * x), 0. ],
+          [ 2. * (x * z + w * y), 2. * (y * z - w * x), 1. - 2. * (x * x + y * y), 0. ],
+          [ 0., 0., 0., 1. ] ]
+    }
+
+
+
+    /// Turns from `self` at `t` = 0 to `other` at `t` = 1 at a constant speed, the short way round.
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Self
+    {
+        let mut dot = self.dot(other);
+        let mut other = *other;
+
+        if dot < 0. {
+            dot = -dot;
+            other = Quaternion { w: -other.w, x: -other.x, y: -other.y, z: -other.z };
+        }
+
+        let (a, b) = match dot > SLERP_LINEAR_DOT {
+            true => (1. - t, t),
+            false => {
+                let angle = dot.acos();
+                let sin = angle.sin();
+
+                (((1. - t) * angle).sin() / sin, (t * angle).sin() / sin)
+            },
+        };
+
+        Quaternion {
+            w: self.w * a + other.w * b,
+            x: self.x * a + other.x * b,
+            y: self.y * a + other.y * b,
+            z: self.z * a + other.z * b,
+        }.normalized()
+    }
+}
+
+
+
+/// `a * b` turns by `b` first and then by `a`.
+impl std::ops::Mul for Quaternion
+{
+    type Output = Quaternion;
+
+    fn mul(self, b: Quaternion) -> Quaternion
+    {
+        let a = self;
+
+        Quaternion {
+            w: a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+            x: a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            y: a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            z: a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+#![allow(dead_code)]
+
+use vecmath::Matrix4;
+
+use crate::{
+    mesh_cache::MeshCache,
+    render_backend_trait::{DrawCall, RenderBackend},
+};
+
+
+#[derive(Clone, Debug)]
+pub struct RecordedFrame
+{
+    pub view_proj: Matrix4<f32>,
+    pub calls: Vec<DrawCall>,
+}
+
+
+
+/// Backend that draws nothing and keeps every submitted frame, the game runs on it without any OpenGL context.
+pub struct RecordingBackend
+{
+    viewport: [u32; 2],
+    pub frames: Vec<RecordedFrame>,
+}
+
+
+
+impl RecordingBackend
+{
+    pub fn new(viewport: [u32; 2]) -> Self
+    {
+        RecordingBackend {
+            viewport,
+            frames: Vec::new(),
+        }
+    }
+
+
+
+    pub fn last_frame(&self) -> Option<&RecordedFrame>
+    {
+        self.frames.last()
+    }
+}
+
+
+
+impl RenderBackend for RecordingBackend
+{
+    fn viewport(&self) -> [u32; 2]
+    {
+        self.viewport
+    }
+
+
+
+    fn draw(&mut self, view_proj: &Matrix4<f32>, calls: &[DrawCall], _meshes: &MeshCache)
+    {
+        self.frames.push(RecordedFrame { view_proj: *view_proj, calls: calls.to_vec() });
+    }
+}
@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+use vecmath::Matrix4;
+
+use crate::{
+    base_mesh_trait::Instance,
+    mesh_cache::{MeshCache, MeshHandle},
+};
+
+
+/// Every instance of one mesh drawn in a frame, the backends draw it with a single call.
+#[derive(Clone, Debug)]
+pub struct DrawCall
+{
+    pub mesh: MeshHandle,
+    pub instances: Vec<Instance>,
+}
+
+
+
+/// Where the renderer sends its frames: the OpenGL window, or anything that records or rasterises them.
+pub trait RenderBackend
+{
+    /// Size of the target in pixels, the levels of detail are picked from it.
+    fn viewport(&self) -> [u32; 2];
+
+
+    /// Draws one frame, `meshes` holds the triangles of every handle the calls use.
+    fn draw(&mut self, view_proj: &Matrix4<f32>, calls: &[DrawCall], meshes: &MeshCache);
+}
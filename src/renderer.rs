@@ -0,0 +1,185 @@
+#![allow(dead_code)]
+
+use vecmath::Matrix4;
+
+use crate::{
+    actor_trait::ActorBase,
+    base_mesh_trait::{Instance, MeshDesc},
+    mesh_cache::{MeshCache, MeshHandle},
+    mesh_error::MeshError,
+    render_backend_trait::{DrawCall, RenderBackend},
+};
+
+
+// On-screen diameter in pixels below which the next coarser level of detail is drawn.
+const LOD_PIXEL_SIZES: [f32; 2] = [ 150., 50. ];
+
+
+
+// Rough diameter of the instance on the screen in pixels, from its bounding sphere.
+fn screen_size(instance:        &Instance,
+               radius:          f32,
+               camera:          &Matrix4<f32>,
+               projection:      &Matrix4<f32>,
+               viewport_height: f32) -> f32
+{
+    let model = [ instance.i_model_0, instance.i_model_1, instance.i_model_2, instance.i_model_3 ];
+    let scale = model[..3].iter()
+                          .map(|column| vecmath::vec3_len([ column[0], column[1], column[2] ]))
+                          .fold(0., f32::max);
+    let center = vecmath::col_mat4_transform(*camera, model[3]);
+    let depth = -center[2];
+
+    // Behind the camera, nothing of it is seen.
+    if depth <= 0. {
+        return 0.
+    }
+
+    2. * (radius + instance.i_ring_growth) * scale * projection[1][1] / depth * viewport_height * 0.5
+}
+
+
+
+// Every actor sharing one mesh, drawn with a single call per level of detail.
+struct Batch
+{
+    mesh: MeshHandle,
+    instances: Vec<Instance>,
+
+    // Looked up in the cache the first time the batch is drawn, the finest first.
+    levels: Vec<MeshHandle>,
+    radius: f32,
+}
+
+
+
+/// Collects the actors of a frame, picks their levels of detail and hands the frame to a backend,
+/// one draw call per mesh and level of detail. It owns the meshes the actors are built from.
+pub struct Renderer
+{
+    cache: MeshCache,
+
+    camera: Matrix4<f32>,
+    projection: Matrix4<f32>,
+    batches: Vec<Batch>,
+    // The calls of the last submitted frame.
+    calls: Vec<DrawCall>,
+}
+
+
+
+impl Renderer
+{
+    pub fn new() -> Self
+    {
+        Renderer {
+            cache: MeshCache::new(),
+            camera: vecmath::mat4_id(),
+            projection: vecmath::mat4_id(),
+            batches: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+
+
+    pub fn cache(&self) -> &MeshCache
+    {
+        &self.cache
+    }
+
+
+
+    pub fn cache_mut(&mut self) -> &mut MeshCache
+    {
+        &mut self.cache
+    }
+
+
+
+    /// The shared mesh of `mesh_desc`, actors keep the handle.
+    pub fn mesh(&mut self, mesh_desc: &MeshDesc) -> Result<MeshHandle, MeshError>
+    {
+        self.cache.mesh(mesh_desc)
+    }
+
+
+
+    pub fn begin(&mut self, camera: &Matrix4<f32>, projection: &Matrix4<f32>)
+    {
+        self.camera = *camera;
+        self.projection = *projection;
+        self.batches.iter_mut().for_each(|batch| batch.instances.clear());
+    }
+
+
+
+    // Group nodes have nothing to draw and are skipped.
+    pub fn push(&mut self, base: &ActorBase)
+    {
+        let Some(mesh) = base.mesh else {
+            return;
+        };
+
+        let instance = Instance::new(base.get_model(), base.color, base.ring_growth);
+
+        match self.batches.iter_mut().find(|batch| batch.mesh == mesh)
+        {
+            Some(batch) => batch.instances.push(instance),
+            None => self.batches.push(Batch {
+                mesh,
+                instances: vec![ instance ],
+                levels: Vec::new(),
+                radius: f32::INFINITY,
+            }),
+        }
+    }
+
+
+
+    /// Draws the actors pushed since `begin` with `backend`.
+    pub fn submit(&mut self, backend: &mut dyn RenderBackend)
+    {
+        let view_proj = vecmath::col_mat4_mul(self.projection, self.camera);
+        let viewport_height = backend.viewport()[1] as f32;
+
+        self.calls.clear();
+
+        for batch in self.batches.iter_mut()
+        {
+            if batch.levels.is_empty() {
+                batch.levels = self.cache.lods(batch.mesh);
+                batch.radius = self.cache.get(batch.mesh).radius;
+            }
+
+            let mut levels: Vec<Vec<Instance>> = vec![ Vec::new(); batch.levels.len() ];
+
+            for instance in batch.instances.iter()
+            {
+                let size = screen_size(instance, batch.radius, &self.camera, &self.projection, viewport_height);
+                let level = LOD_PIXEL_SIZES.iter()
+                                           .take_while(|pixels| size < **pixels)
+                                           .count()
+                                           .min(levels.len() - 1);
+
+                levels[level].push(*instance);
+            }
+
+            self.calls.extend(batch.levels
+                                   .iter()
+                                   .zip(levels)
+                                   .filter(|(_, instances)| !instances.is_empty())
+                                   .map(|(mesh, instances)| DrawCall { mesh: *mesh, instances }));
+        }
+
+        backend.draw(&view_proj, &self.calls, &self.cache);
+    }
+
+
+
+    /// Draw calls the last submitted frame needed.
+    pub fn draw_calls(&self) -> usize
+    {
+        self.calls.len()
+    }
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use std::rc::Rc;
+
+use crate::{
+    adjacent_rules::AdjacentRules,
+    bicolor_rules::BicolorRules,
+    classic_rules::ClassicRules,
+    custom_rules::CustomRules,
+    cyclic_rules::CyclicRules,
+    equal_size_rules::EqualSizeRules,
+    magnetic_rules::MagneticRules,
+    rule_set_trait::RuleSet,
+};
+
+
+const CUSTOM_PREFIX: &str = "custom:";
+
+
+pub struct RuleSetRegistry
+{
+    rule_sets: Vec<Rc<dyn RuleSet>>,
+}
+
+
+
+impl RuleSetRegistry
+{
+    pub fn new() -> Self
+    {
+        RuleSetRegistry {
+            rule_sets: Vec::new(),
+        }
+    }
+
+
+
+    pub fn with_builtin() -> Self
+    {
+        let mut registry = RuleSetRegistry::new();
+
+        registry.register(Rc::new(ClassicRules));
+        registry.register(Rc::new(CyclicRules));
+        registry.register(Rc::new(AdjacentRules));
+        registry.register(Rc::new(EqualSizeRules));
+        registry.register(Rc::new(BicolorRules { groups: 2 }));
+        registry.register(Rc::new(BicolorRules { groups: 3 }));
+        registry.register(Rc::new(MagneticRules));
+
+        registry
+    }
+
+
+
+    /// Registers the rule set under its name, replacing one registered before with the same name.
+    pub fn register(&mut self, rule_set: Rc<dyn RuleSet>)
+    {
+        self.rule_sets.retain(|registered| registered.name() != rule_set.name());
+        self.rule_sets.push(rule_set);
+    }
+
+
+
+    /// Finds a registered rule set, "custom:<moves>" builds one from a list of allowed moves.
+    pub fn get(&self, name: &str) -> Option<Rc<dyn RuleSet>>
+    {
+        if let Some(registered) = self.rule_sets.iter().find(|rule_set| rule_set.name() == name) {
+            return Some(registered.clone());
+        }
+
+        let spec = name.strip_prefix(CUSTOM_PREFIX)?;
+
+        Some(Rc::new(CustomRules::from_spec(name, spec)?))
+    }
+
+
+
+    pub fn names(&self) -> Vec<&str>
+    {
+        self.rule_sets.iter().map(|rule_set| rule_set.name()).collect()
+    }
+}
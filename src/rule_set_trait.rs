@@ -0,0 +1,154 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+use crate::{
+    game_settings::GameSettings,
+    solver::{self, AutoGameMove},
+    tower_state::{Disk, Peg, TowerState},
+};
+
+
+
+/// Checks shared by most rule sets: a disk is taken from a different, non empty stick and
+/// isn't put on a narrower one. Equal widths may be stacked only with `allow_equal`.
+pub fn fits_by_width(state: &TowerState, from: Peg, to: Peg, allow_equal: bool) -> bool
+{
+    if from == to {
+        return false
+    }
+
+    let Some(moved) = state.top(from) else {
+        return false
+    };
+
+    match state.top(to)
+    {
+        Some(below) if allow_equal => moved.width <= below.width,
+        Some(below) => moved.width < below.width,
+        None => true,
+    }
+}
+
+
+
+pub trait RuleSet
+{
+    fn name(&self) -> &str;
+
+
+    fn is_move_legal(&self, state: &TowerState, from: Peg, to: Peg) -> bool;
+
+
+    /// Whether disks of the same width may be stacked, games with repeated sizes need it.
+    fn allows_equal_widths(&self) -> bool
+    {
+        false
+    }
+
+
+    /// Whether the starting tower is built from the widths given with --sizes.
+    fn uses_disk_sizes(&self) -> bool
+    {
+        true
+    }
+
+
+    fn apply_move(&self, state: &mut TowerState, from: Peg, to: Peg)
+    {
+        state.move_disk(from, to);
+    }
+
+
+    fn is_won(&self, state: &TowerState, target: &TowerState) -> bool
+    {
+        state == target
+    }
+
+
+    /// Dedicated solver of the rule set, `None` leaves the job to the breadth first search.
+    fn solve(&self, _from: &TowerState, _to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        None
+    }
+
+
+
+    fn start_state(&self, settings: &GameSettings) -> TowerState
+    {
+        settings.tower(settings.start_peg)
+    }
+
+
+    fn goal_state(&self, settings: &GameSettings) -> TowerState
+    {
+        settings.tower(settings.goal_peg)
+    }
+
+
+    /// Random target layout `distance` optimal moves away from `start`.
+    fn random_target(&self, start: &TowerState, _start_peg: Peg, distance: u64) -> TowerState
+    {
+        solver::random_state_by_search(start, distance, |state, a, b| self.try_move(state, a, b))
+    }
+
+
+    /// Colour telling apart what matters for the rules, `None` keeps the random one.
+    fn donut_color(&self, _disk: &Disk) -> Option<[f32; 4]>
+    {
+        None
+    }
+
+
+    /// Moves between sticks the rules restrict disks to, shown as arrows on the ground.
+    fn allowed_directions(&self) -> Option<Vec<AutoGameMove>>
+    {
+        None
+    }
+
+
+
+    /// The layout after the move, `None` if the move isn't legal.
+    fn try_move(&self, state: &TowerState, from: Peg, to: Peg) -> Option<TowerState>
+    {
+        if !self.is_move_legal(state, from, to) {
+            return None
+        }
+
+        let mut next = state.clone();
+        self.apply_move(&mut next, from, to);
+
+        Some(next)
+    }
+
+
+    fn plan_moves(&self, from: &TowerState, to: &TowerState) -> Option<VecDeque<AutoGameMove>>
+    {
+        self.solve(from, to).or_else(|| solver::moves_by_search(from,
+                                                                |state| self.is_won(state, to),
+                                                                |state, a, b| self.try_move(state, a, b)))
+    }
+
+
+    /// The first moves of an optimal plan, at least `limit` of them unless the goal is closer.
+    /// Rules telling the next move cheaply stop there, the default plans the whole route.
+    fn next_moves(&self, from: &TowerState, to: &TowerState, _limit: usize) -> Option<VecDeque<AutoGameMove>>
+    {
+        self.plan_moves(from, to)
+    }
+
+
+    /// Amount of optimal moves between the layouts, `None` when no plan is found.
+    fn distance(&self, from: &TowerState, to: &TowerState) -> Option<u64>
+    {
+        self.plan_moves(from, to).map(|moves| moves.len() as u64)
+    }
+
+
+    /// Most disks the game is played with, the plans grow exponentially with the amount.
+    /// The breadth first search gives up somewhere above 3^13 layouts.
+    fn max_disks_amount(&self) -> i32
+    {
+        13
+    }
+}
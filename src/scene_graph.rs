@@ -0,0 +1,121 @@
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{
+    actor_trait::ActorBase,
+};
+
+
+/// A node of the scene graph, actors share theirs with the animator.
+pub type SceneNode = Rc<RefCell<ActorBase>>;
+
+
+
+/// A node without a mesh that only places its children.
+pub fn group(position: vecmath::Vector3<f32>) -> SceneNode
+{
+    let node = Rc::new(RefCell::new(ActorBase::group()));
+    node.borrow_mut().set_position(position);
+
+    node
+}
+
+
+
+/// Makes `child` follow `parent`, its transform is taken relative to the parent's from now on.
+/// Nothing changes when `parent` is `child` or one of its descendants, the graph would turn into a loop,
+/// the result tells whether `child` was attached.
+pub fn attach(parent: &SceneNode, child: &SceneNode) -> bool
+{
+    if is_within(parent, child) {
+        return false
+    }
+
+    detach(child);
+
+    parent.borrow_mut().children.push(Rc::downgrade(child));
+
+    let mut child = child.borrow_mut();
+    child.parent = Some(Rc::downgrade(parent));
+    child.mark_dirty();
+
+    true
+}
+
+
+
+/// Takes `node` out of its parent, its transform is the world one from now on.
+pub fn detach(node: &SceneNode)
+{
+    let parent = node.borrow_mut().parent.take();
+
+    if let Some(parent) = parent.and_then(|parent| parent.upgrade()) {
+        parent.borrow_mut().children.retain(|child| child.as_ptr() != Rc::as_ptr(node));
+    }
+
+    node.borrow_mut().mark_dirty();
+}
+
+
+
+/// Moves `child` under `parent` where it is in the world, only the position is carried over,
+/// the rotation and scale stay relative to the parent. Like `attach` it refuses to make a loop.
+pub fn reparent(parent: &SceneNode, child: &SceneNode) -> bool
+{
+    let position = world_matrix(child)[3];
+
+    if !attach(parent, child) {
+        return false
+    }
+
+    let local = vecmath::col_mat4_transform(vecmath::mat4_inv(world_matrix(parent)), position);
+    child.borrow_mut().set_position([ local[0], local[1], local[2] ]);
+
+    true
+}
+
+
+
+/// The up to date world matrix of `node`.
+pub fn world_matrix(node: &SceneNode) -> vecmath::Matrix4<f32>
+{
+    let mut node = node.borrow_mut();
+    node.update_world();
+
+    node.get_model()
+}
+
+
+
+pub fn parent(node: &SceneNode) -> Option<SceneNode>
+{
+    node.borrow().parent.as_ref().and_then(|parent| parent.upgrade())
+}
+
+
+
+pub fn children(node: &SceneNode) -> Vec<SceneNode>
+{
+    node.borrow().children.iter().filter_map(|child| child.upgrade()).collect()
+}
+
+
+
+/// Whether `node` is `ancestor` itself or somewhere below it.
+pub fn is_within(node: &SceneNode, ancestor: &SceneNode) -> bool
+{
+    let mut current = Some(node.clone());
+
+    while let Some(node) = current
+    {
+        if Rc::ptr_eq(&node, ancestor) {
+            return true
+        }
+
+        current = parent(&node);
+    }
+
+    false
+}
@@ -0,0 +1,271 @@
+#![allow(dead_code)]
+
+use image::{Rgba, RgbaImage};
+use vecmath::{Matrix4, Vector3};
+
+use crate::{
+    base_mesh_trait,
+    mesh_cache::MeshCache,
+    mesh_error::MeshError,
+    render_backend_trait::{DrawCall, RenderBackend},
+};
+
+
+// The constants of shader_150_instanced.frag.
+const LIGHT_POS: Vector3<f32> = [ 15.0, -20.0, 20.0 ];
+const LIGHT_COLOR: Vector3<f32> = [ 1.0, 0.9, 1.0 ];
+const VIEW_POS: Vector3<f32> = [ 0.5, 10.5, 21.0 ];
+const AMBIENT_STRENGTH: f32 = 0.1;
+const DIFFUSE_STRENGTH: f32 = 1.4;
+const SPECULAR_STRENGTH: f32 = 21.;
+const SHININESS: f32 = 20.;
+
+const CLEAR_COLOR: Rgba<u8> = Rgba([ 0, 0, 0, 255 ]);
+
+// What the vertex shader hands over per corner: the clip position, the world position and the normal.
+type Corner = [f32; 10];
+
+
+
+// The fragment shader, `frag_pos` and `normal` are in world space.
+fn shade(frag_pos: Vector3<f32>, normal: Vector3<f32>, color: [f32; 4]) -> Vector3<f32>
+{
+    let norm = vecmath::vec3_normalized(normal);
+    let light_dir = vecmath::vec3_normalized(vecmath::vec3_sub(LIGHT_POS, frag_pos));
+    let diff = vecmath::vec3_dot(norm, light_dir).max(0.);
+
+    let view_dir = vecmath::vec3_normalized(vecmath::vec3_sub(VIEW_POS, frag_pos));
+    let incident = vecmath::vec3_neg(light_dir);
+    let reflect_dir = vecmath::vec3_sub(incident, vecmath::vec3_scale(norm, 2. * vecmath::vec3_dot(norm, incident)));
+    let spec = vecmath::vec3_dot(view_dir, reflect_dir).max(0.).powf(SHININESS);
+
+    let light = AMBIENT_STRENGTH + DIFFUSE_STRENGTH * diff + SPECULAR_STRENGTH * spec;
+
+    [ light * LIGHT_COLOR[0] * color[0],
+      light * LIGHT_COLOR[1] * color[1],
+      light * LIGHT_COLOR[2] * color[2] ]
+}
+
+
+
+// The window target is sRGB, the shader output is encoded on the way in.
+fn to_srgb(linear: f32) -> u8
+{
+    let linear = linear.clamp(0., 1.);
+    let encoded = match linear <= 0.0031308 {
+        true => linear * 12.92,
+        false => 1.055 * linear.powf(1. / 2.4) - 0.055,
+    };
+
+    (encoded * 255.).round() as u8
+}
+
+
+
+// Keeps the part of the polygon in front of the near plane and behind the far one,
+// the corners start with their clip position.
+fn clip_polygon(polygon: Vec<Corner>) -> Vec<Corner>
+{
+    let planes: [fn(&Corner) -> f32; 2] = [ |v| v[2] + v[3], |v| v[3] - v[2] ];
+    let mut polygon = polygon;
+
+    for distance in planes
+    {
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+        for (i, current) in polygon.iter().enumerate()
+        {
+            let next = &polygon[(i + 1) % polygon.len()];
+            let (d_current, d_next) = (distance(current), distance(next));
+
+            if d_current >= 0. {
+                clipped.push(*current);
+            }
+
+            if (d_current >= 0.) != (d_next >= 0.) {
+                let t = d_current / (d_current - d_next);
+                clipped.push(std::array::from_fn(|axis| current[axis] + (next[axis] - current[axis]) * t));
+            }
+        }
+
+        polygon = clipped;
+
+        if polygon.is_empty() {
+            break;
+        }
+    }
+
+    polygon
+}
+
+
+
+// Twice the signed area of the triangle `a`, `b`, `p` on the screen.
+fn edge(a: &[f32; 4], b: &[f32; 4], p: [f32; 2]) -> f32
+{
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+
+
+/// Rasterises the frames on the CPU with the lighting of the instanced shaders into an RGBA image,
+/// for screenshots and tests on machines without a GPU.
+pub struct SoftwareBackend
+{
+    image: RgbaImage,
+    depth: Vec<f32>,
+}
+
+
+
+impl SoftwareBackend
+{
+    pub fn new(width: u32, height: u32) -> Self
+    {
+        SoftwareBackend {
+            image: RgbaImage::from_pixel(width, height, CLEAR_COLOR),
+            depth: vec![ 1.; (width * height) as usize ],
+        }
+    }
+
+
+
+    /// The last drawn frame.
+    pub fn image(&self) -> &RgbaImage
+    {
+        &self.image
+    }
+
+
+
+    /// Saves the last drawn frame, the format follows the extension of `path`.
+    pub fn save(&self, path: &str) -> Result<(), MeshError>
+    {
+        self.image.save(path).map_err(|error| MeshError::Io { path: path.to_string(), message: error.to_string() })
+    }
+
+
+
+    fn clear(&mut self)
+    {
+        self.image.pixels_mut().for_each(|pixel| *pixel = CLEAR_COLOR);
+        self.depth.iter_mut().for_each(|depth| *depth = 1.);
+    }
+
+
+
+    // The world positions and the normals of the corners are interpolated like the GPU would.
+    fn draw_triangle(&mut self, corners: [Corner; 3], color: [f32; 4])
+    {
+        let (width, height) = self.image.dimensions();
+        let polygon = clip_polygon(corners.to_vec());
+
+        // Screen position, depth and 1/w of every corner.
+        let screen: Vec<[f32; 4]> = polygon.iter()
+                                           .map(|v| {
+                                               let inv_w = 1. / v[3];
+                                               [ (v[0] * inv_w * 0.5 + 0.5) * width as f32,
+                                                 (0.5 - v[1] * inv_w * 0.5) * height as f32,
+                                                 v[2] * inv_w * 0.5 + 0.5,
+                                                 inv_w ]
+                                           })
+                                           .collect();
+
+        for i in 1..screen.len().saturating_sub(1)
+        {
+            let corners = [ 0, i, i + 1 ];
+            let [ s0, s1, s2 ] = corners.map(|corner| screen[corner]);
+            let area = edge(&s0, &s1, s2[..2].try_into().unwrap());
+
+            if area == 0. || !area.is_finite() {
+                continue;
+            }
+
+            let min_x = s0[0].min(s1[0]).min(s2[0]).floor().max(0.) as u32;
+            let min_y = s0[1].min(s1[1]).min(s2[1]).floor().max(0.) as u32;
+            let max_x = (s0[0].max(s1[0]).max(s2[0]).ceil().max(0.) as u32).min(width);
+            let max_y = (s0[1].max(s1[1]).max(s2[1]).ceil().max(0.) as u32).min(height);
+
+            for y in min_y..max_y
+            {
+                for x in min_x..max_x
+                {
+                    let p = [ x as f32 + 0.5, y as f32 + 0.5 ];
+                    let weights = [ edge(&s1, &s2, p) / area, edge(&s2, &s0, p) / area, edge(&s0, &s1, p) / area ];
+
+                    if weights.iter().any(|weight| *weight < 0.) {
+                        continue;
+                    }
+
+                    let index = (y * width + x) as usize;
+                    let depth = weights[0] * s0[2] + weights[1] * s1[2] + weights[2] * s2[2];
+
+                    if depth > self.depth[index] {
+                        continue;
+                    }
+
+                    // Perspective correct, the same as the varyings of the shader.
+                    let perspective = [ weights[0] * s0[3], weights[1] * s1[3], weights[2] * s2[3] ];
+                    let sum: f32 = perspective.iter().sum();
+                    let varying: [f32; 6] = std::array::from_fn(|i| {
+                        corners.iter()
+                               .zip(perspective)
+                               .map(|(corner, weight)| polygon[*corner][4 + i] * weight)
+                               .sum::<f32>() / sum
+                    });
+                    let result = shade([ varying[0], varying[1], varying[2] ], [ varying[3], varying[4], varying[5] ], color);
+
+                    self.depth[index] = depth;
+                    self.image.put_pixel(x, y, Rgba([ to_srgb(result[0]), to_srgb(result[1]), to_srgb(result[2]), 255 ]));
+                }
+            }
+        }
+    }
+}
+
+
+
+impl RenderBackend for SoftwareBackend
+{
+    fn viewport(&self) -> [u32; 2]
+    {
+        [ self.image.width(), self.image.height() ]
+    }
+
+
+
+    fn draw(&mut self, view_proj: &Matrix4<f32>, calls: &[DrawCall], meshes: &MeshCache)
+    {
+        self.clear();
+
+        for call in calls
+        {
+            let mesh = meshes.get(call.mesh).data.clone();
+
+            for instance in call.instances.iter()
+            {
+                let model = [ instance.i_model_0, instance.i_model_1, instance.i_model_2, instance.i_model_3 ];
+
+                // mat3(transpose(inverse(model))), the columns of the transpose are the rows of the inverse.
+                let inverse = vecmath::mat4_inv(model);
+                let normal_matrix = [ 0, 1, 2 ].map(|column| [ inverse[0][column], inverse[1][column], inverse[2][column] ]);
+
+                let corners: Vec<Corner> = mesh.vertices
+                                               .iter()
+                                               .map(|vertex| {
+                                                   let world = vecmath::col_mat4_transform(model, base_mesh_trait::grow_ring(vertex.a_pos, instance.i_ring_growth));
+                                                   let clip = vecmath::col_mat4_transform(*view_proj, world);
+                                                   let normal = vecmath::col_mat3_transform(normal_matrix, vertex.a_normal);
+
+                                                   [ clip[0], clip[1], clip[2], clip[3], world[0], world[1], world[2], normal[0], normal[1], normal[2] ]
+                                               })
+                                               .collect();
+
+                for triangle in mesh.indices.chunks_exact(3)
+                {
+                    self.draw_triangle(std::array::from_fn(|i| corners[triangle[i] as usize]), instance.i_color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,557 @@
+#![allow(dead_code)]
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::tower_state::{Disk, Peg, TowerState, PEGS_AMOUNT};
+
+
+// Breadth first search gives up past this many visited layouts.
+const MAX_SEARCHED_STATES: usize = 2_000_000;
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AutoGameMove
+{
+    pub from: Peg,
+    pub to: Peg,
+}
+
+
+
+#[inline]
+fn third_peg(a: Peg, b: Peg) -> Peg
+{
+    3 - a - b
+}
+
+
+
+pub fn generate_moves(target: &mut VecDeque<AutoGameMove>, n: i32, from: Peg, to: Peg, aux: Peg)
+{
+    if n == 0 {
+        return;
+    }
+
+    generate_moves(target, n - 1, from, aux, to);
+    target.push_back(AutoGameMove { from, to });
+    generate_moves(target, n - 1, aux, to, from);
+}
+
+
+
+/// Minimal amount of moves that gathers the disks from `assignment` into a single tower on `peg`.
+pub fn distance_to_tower(assignment: &[Peg], peg: Peg) -> u64
+{
+    let mut target = peg;
+    let mut distance = 0;
+
+    for (i, current) in assignment.iter().enumerate().rev()
+    {
+        if *current != target {
+            distance = 1u64.checked_shl(i as u32).map_or(u64::MAX, |moves| distance.saturating_add(moves));
+            target = third_peg(*current, target);
+        }
+    }
+
+    distance
+}
+
+
+
+/// Optimal moves gathering the disks from `assignment` into a single tower on `peg`.
+pub fn moves_to_tower(target: &mut VecDeque<AutoGameMove>, assignment: &[Peg], peg: Peg)
+{
+    let Some((current, smaller)) = assignment.split_last() else {
+        return;
+    };
+
+    if *current == peg {
+        moves_to_tower(target, smaller, peg);
+        return;
+    }
+
+    let aux = third_peg(*current, peg);
+
+    moves_to_tower(target, smaller, aux);
+    target.push_back(AutoGameMove { from: *current, to: peg });
+    generate_moves(target, smaller.len() as i32, aux, peg, *current);
+}
+
+
+
+// First of the moves gathering the disks into a tower on `peg`: the narrowest disk off its target stick
+// goes there, it's the last one found walking down from the widest disk.
+fn first_move_to_tower(assignment: &[Peg], peg: Peg) -> Option<AutoGameMove>
+{
+    let mut target = peg;
+    let mut first = None;
+
+    for current in assignment.iter().rev()
+    {
+        if *current != target {
+            first = Some(AutoGameMove { from: *current, to: target });
+            target = third_peg(*current, target);
+        }
+    }
+
+    first
+}
+
+
+
+fn reversed_moves_from_tower(target: &mut VecDeque<AutoGameMove>, assignment: &[Peg], peg: Peg)
+{
+    let mut moves = VecDeque::new();
+    moves_to_tower(&mut moves, assignment, peg);
+
+    target.extend(moves.into_iter()
+                       .rev()
+                       .map(|game_move| AutoGameMove { from: game_move.to, to: game_move.from }));
+}
+
+
+
+// The widest disk placed differently in `from` and `to`, with the cost of moving it once (a -> b)
+// and twice (a -> c -> b) together with the smaller disks.
+fn crossing_costs(from: &[Peg], to: &[Peg]) -> Option<(usize, u64, u64)>
+{
+    let largest = (0..from.len()).rev().find(|i| from[*i] != to[*i])?;
+
+    let a = from[largest];
+    let b = to[largest];
+    let c = third_peg(a, b);
+    let from_smaller = &from[..largest];
+    let to_smaller = &to[..largest];
+
+    let smaller_tower = 1u64.checked_shl(largest as u32).map_or(u64::MAX, |moves| moves - 1);
+
+    let once = distance_to_tower(from_smaller, c).saturating_add(1)
+                                                 .saturating_add(distance_to_tower(to_smaller, c));
+    let twice = distance_to_tower(from_smaller, b).saturating_add(2)
+                                                  .saturating_add(smaller_tower)
+                                                  .saturating_add(distance_to_tower(to_smaller, a));
+
+    Some((largest, once, twice))
+}
+
+
+
+/// Minimal amount of moves between two legal layouts of the same disks, saturated at `u64::MAX`.
+pub fn distance_between(from: &[Peg], to: &[Peg]) -> u64
+{
+    crossing_costs(from, to).map_or(0, |(_, once, twice)| once.min(twice))
+}
+
+
+
+/// First move of `moves_between` without planning the rest, `None` when the layouts are the same.
+pub fn next_move_between(from: &[Peg], to: &[Peg]) -> Option<AutoGameMove>
+{
+    let (largest, once, twice) = crossing_costs(from, to)?;
+
+    let a = from[largest];
+    let b = to[largest];
+    let c = third_peg(a, b);
+
+    match once <= twice
+    {
+        true => first_move_to_tower(&from[..largest], c).or(Some(AutoGameMove { from: a, to: b })),
+        false => first_move_to_tower(&from[..largest], b).or(Some(AutoGameMove { from: a, to: c })),
+    }
+}
+
+
+
+/// Optimal moves between two arbitrary legal layouts of the same disks.
+pub fn moves_between(target: &mut VecDeque<AutoGameMove>, from: &[Peg], to: &[Peg])
+{
+    let Some((largest, once, twice)) = crossing_costs(from, to) else {
+        return;
+    };
+
+    let a = from[largest];
+    let b = to[largest];
+    let c = third_peg(a, b);
+    let from_smaller = &from[..largest];
+    let to_smaller = &to[..largest];
+
+    // The largest differing disk is moved either once or twice, whichever is cheaper wins.
+    if once <= twice {
+        moves_to_tower(target, from_smaller, c);
+        target.push_back(AutoGameMove { from: a, to: b });
+        reversed_moves_from_tower(target, to_smaller, c);
+    } else {
+        moves_to_tower(target, from_smaller, b);
+        target.push_back(AutoGameMove { from: a, to: c });
+        generate_moves(target, largest as i32, b, a, c);
+        target.push_back(AutoGameMove { from: c, to: b });
+        reversed_moves_from_tower(target, to_smaller, a);
+    }
+}
+
+
+
+/// Random layout that is exactly `distance` optimal moves away from a full tower on `start`.
+pub fn random_assignment_at_distance(disks_amount: usize, start: Peg, distance: u64) -> Vec<Peg>
+{
+    // Beyond 64 disks every u64 distance can be reached.
+    let max_distance = 1u64.checked_shl(disks_amount as u32).map_or(u64::MAX, |moves| moves - 1);
+    let distance = distance.min(max_distance);
+
+    let mut random = rand::thread_rng();
+    let mut assignment = vec![0; disks_amount];
+    let mut target = start;
+
+    for i in (0..disks_amount).rev()
+    {
+        if distance & 1u64.checked_shl(i as u32).unwrap_or(0) == 0 {
+            assignment[i] = target;
+            continue;
+        }
+
+        let other = (target + random.gen_range(1..3)) % 3;
+        assignment[i] = other;
+        target = third_peg(other, target);
+    }
+
+    assignment
+}
+
+
+
+/// Optimal moves of a tower of `n` disks one stick clockwise, `from -> from + 1`,
+/// when disks may only ever move clockwise.
+pub fn generate_cyclic_moves_one(target: &mut VecDeque<AutoGameMove>, n: i32, from: Peg)
+{
+    if n == 0 {
+        return;
+    }
+
+    let next = (from + 1) % 3;
+    let last = (from + 2) % 3;
+
+    generate_cyclic_moves_two(target, n - 1, from);
+    target.push_back(AutoGameMove { from, to: next });
+    generate_cyclic_moves_two(target, n - 1, last);
+}
+
+
+
+/// Optimal moves of a tower of `n` disks two sticks clockwise, `from -> from + 2`.
+pub fn generate_cyclic_moves_two(target: &mut VecDeque<AutoGameMove>, n: i32, from: Peg)
+{
+    if n == 0 {
+        return;
+    }
+
+    let next = (from + 1) % 3;
+    let last = (from + 2) % 3;
+
+    generate_cyclic_moves_two(target, n - 1, from);
+    target.push_back(AutoGameMove { from, to: next });
+    generate_cyclic_moves_one(target, n - 1, last);
+    target.push_back(AutoGameMove { from: next, to: last });
+    generate_cyclic_moves_two(target, n - 1, from);
+}
+
+
+
+/// Optimal moves of a tower of `n` disks when disks may only move between neighbouring sticks.
+pub fn generate_adjacent_moves(target: &mut VecDeque<AutoGameMove>, n: i32, from: Peg, to: Peg)
+{
+    if n == 0 || from == to {
+        return;
+    }
+
+    let other = third_peg(from, to);
+
+    if from.abs_diff(to) == 1 {
+        generate_adjacent_moves(target, n - 1, from, other);
+        target.push_back(AutoGameMove { from, to });
+        generate_adjacent_moves(target, n - 1, other, to);
+        return;
+    }
+
+    // Between the outer sticks every disk has to stop on the middle one.
+    generate_adjacent_moves(target, n - 1, from, to);
+    target.push_back(AutoGameMove { from, to: other });
+    generate_adjacent_moves(target, n - 1, to, from);
+    target.push_back(AutoGameMove { from: other, to });
+    generate_adjacent_moves(target, n - 1, from, to);
+}
+
+
+
+/// Moves a tower whose widths appear `counts[i]` times, smallest width first, disks sharing
+/// a width travel one after another and end up in reversed order.
+pub fn generate_grouped_moves(target: &mut VecDeque<AutoGameMove>, counts: &[usize], from: Peg, to: Peg)
+{
+    let Some((largest, smaller)) = counts.split_last() else {
+        return;
+    };
+
+    let aux = third_peg(from, to);
+
+    generate_grouped_moves(target, smaller, from, aux);
+    target.extend(std::iter::repeat_n(AutoGameMove { from, to }, *largest));
+    generate_grouped_moves(target, smaller, aux, to);
+}
+
+
+
+fn grouped_moves_amount(counts: &[usize]) -> u64
+{
+    counts.iter()
+          .rev()
+          .enumerate()
+          .map(|(i, count)| 1u64.checked_shl(i as u32).map_or(u64::MAX, |moves| moves.saturating_mul(*count as u64)))
+          .fold(0, u64::saturating_add)
+}
+
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+enum LevelItem
+{
+    Disk(u8),
+    // Every disk narrower than the current level, moved as one grouped tower.
+    Smaller,
+}
+
+
+
+/// Cheapest way to bring the disks of one width from the `source` stick onto their goal
+/// sticks while the narrower disks are carried around as a single block.
+fn plan_level(source: &[Vec<LevelItem>; PEGS_AMOUNT],
+              goal:   &[Vec<LevelItem>; PEGS_AMOUNT],
+              smaller_cost: u64) -> Option<Vec<(LevelItem, AutoGameMove)>>
+{
+    let is_goal = |pegs: &[Vec<LevelItem>; PEGS_AMOUNT]| {
+        pegs.iter()
+            .zip(goal.iter())
+            .all(|(items, wanted)| items.iter().filter(|item| **item != LevelItem::Smaller).eq(wanted.iter()))
+    };
+
+    let mut best = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    best.insert(source.clone(), 0u64);
+    queue.push(Reverse((0, source.clone())));
+
+    while let Some(Reverse((cost, pegs))) = queue.pop()
+    {
+        // A layout is queued again whenever a cheaper way to it turns up, the older entries are stale.
+        if best.get(&pegs).is_some_and(|known| *known < cost) {
+            continue;
+        }
+
+        if is_goal(&pegs) {
+            let mut plan = Vec::new();
+            let mut current = pegs;
+
+            while let Some((parent, step)) = parents.get(&current)
+            {
+                plan.push(*step);
+                current = Clone::clone(parent);
+            }
+
+            plan.reverse();
+            return Some(plan);
+        }
+
+        for from in 0..PEGS_AMOUNT
+        {
+            let Some(item) = pegs[from].last().copied() else {
+                continue;
+            };
+
+            for to in (0..PEGS_AMOUNT).filter(|to| *to != from)
+            {
+                if item != LevelItem::Smaller && pegs[to].last() == Some(&LevelItem::Smaller) {
+                    continue;
+                }
+
+                let mut next = pegs.clone();
+                next[from].pop();
+                next[to].push(item);
+
+                let step_cost = if item == LevelItem::Smaller { smaller_cost } else { 1 };
+                let next_cost = cost.saturating_add(step_cost);
+
+                if best.get(&next).is_some_and(|known| *known <= next_cost) {
+                    continue;
+                }
+
+                best.insert(next.clone(), next_cost);
+                parents.insert(next.clone(), (pegs.clone(), (item, AutoGameMove { from, to })));
+                queue.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+
+
+/// Sorts a single tower into the `goal` layout one width at a time, from the widest disks
+/// down. Disks may share a width, so this also separates interleaved coloured towers.
+pub fn moves_by_sorting(from: &TowerState, goal: &TowerState) -> Option<VecDeque<AutoGameMove>>
+{
+    let mut source = from.tower_peg()?;
+    let mut state = from.clone();
+    let mut moves = VecDeque::new();
+
+    let mut widths: Vec<i32> = from.pegs[source].iter().map(|disk| disk.width).collect();
+    widths.dedup();
+
+    let counts: Vec<usize> = widths.iter()
+                                   .map(|width| from.pegs[source].iter().filter(|disk| disk.width == *width).count())
+                                   .collect();
+
+    for (level, width) in widths.iter().enumerate()
+    {
+        let smaller_counts: Vec<usize> = counts[level + 1..].iter().rev().copied().collect();
+
+        let items_of = |disks: &[Disk]| -> Vec<LevelItem> {
+            disks.iter()
+                 .filter(|disk| disk.width == *width)
+                 .map(|disk| LevelItem::Disk(disk.group))
+                 .collect()
+        };
+
+        let mut start: [Vec<LevelItem>; PEGS_AMOUNT] = Default::default();
+        start[source] = items_of(&state.pegs[source]);
+
+        if !smaller_counts.is_empty() {
+            start[source].push(LevelItem::Smaller);
+        }
+
+        let wanted = [ items_of(&goal.pegs[0]), items_of(&goal.pegs[1]), items_of(&goal.pegs[2]) ];
+        let plan = plan_level(&start, &wanted, grouped_moves_amount(&smaller_counts))?;
+
+        for (item, step) in plan
+        {
+            let mut level_moves = VecDeque::new();
+
+            match item
+            {
+                LevelItem::Smaller => {
+                    generate_grouped_moves(&mut level_moves, &smaller_counts, step.from, step.to);
+                    source = step.to;
+                },
+                LevelItem::Disk(_) => level_moves.push_back(step),
+            }
+
+            for game_move in level_moves
+            {
+                state.move_disk(game_move.from, game_move.to);
+                moves.push_back(game_move);
+            }
+        }
+    }
+
+    if state != *goal {
+        return None;
+    }
+
+    Some(moves)
+}
+
+
+
+// `try_move` returns the layout after the move, or `None` when the rules forbid it.
+fn legal_successors<'a, F>(state: &'a TowerState, try_move: &'a F) -> impl Iterator<Item = (AutoGameMove, TowerState)> + 'a
+    where F: Fn(&TowerState, Peg, Peg) -> Option<TowerState>
+{
+    (0..PEGS_AMOUNT).flat_map(move |from| (0..PEGS_AMOUNT).map(move |to| AutoGameMove { from, to }))
+                    .filter(|game_move| game_move.from != game_move.to)
+                    .filter_map(move |game_move| Some((game_move, try_move(state, game_move.from, game_move.to)?)))
+}
+
+
+
+/// Shortest sequence of legal moves reaching a layout accepted by `is_goal`, found by breadth
+/// first search. `None` when no such layout can be reached or there are too many to search through.
+pub fn moves_by_search<G, F>(from: &TowerState, is_goal: G, try_move: F) -> Option<VecDeque<AutoGameMove>>
+    where G: Fn(&TowerState) -> bool,
+          F: Fn(&TowerState, Peg, Peg) -> Option<TowerState>
+{
+    let mut parents: HashMap<TowerState, Option<(TowerState, AutoGameMove)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    parents.insert(from.clone(), None);
+    queue.push_back(from.clone());
+
+    while let Some(state) = queue.pop_front()
+    {
+        if is_goal(&state) {
+            let mut moves = VecDeque::new();
+            let mut current = state;
+
+            while let Some(Some((parent, game_move))) = parents.get(&current)
+            {
+                moves.push_front(*game_move);
+                current = parent.clone();
+            }
+
+            return Some(moves);
+        }
+
+        if parents.len() > MAX_SEARCHED_STATES {
+            return None;
+        }
+
+        for (game_move, next) in legal_successors(&state, &try_move)
+        {
+            if parents.contains_key(&next) {
+                continue;
+            }
+
+            parents.insert(next.clone(), Some((state.clone(), game_move)));
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+
+
+/// Random layout `distance` optimal moves away from `start`, or as far as the rules allow.
+pub fn random_state_by_search<F>(start: &TowerState, distance: u64, try_move: F) -> TowerState
+    where F: Fn(&TowerState, Peg, Peg) -> Option<TowerState>
+{
+    let mut visited = HashSet::new();
+    let mut layer = vec![start.clone()];
+    visited.insert(start.clone());
+
+    for _ in 0..distance
+    {
+        let mut next_layer = Vec::new();
+
+        for state in &layer
+        {
+            for (_, next) in legal_successors(state, &try_move)
+            {
+                if visited.insert(next.clone()) {
+                    next_layer.push(next);
+                }
+            }
+        }
+
+        if next_layer.is_empty() || visited.len() > MAX_SEARCHED_STATES {
+            break;
+        }
+
+        layer = next_layer;
+    }
+
+    layer.choose(&mut rand::thread_rng()).unwrap().clone()
+}
@@ -0,0 +1,91 @@
+use crate::{math, base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// UV sphere centred at the origin, the poles lie on the Z axis.
+pub struct SphereMeshFactory
+{
+    pub radius: f32,
+    pub segments: isize,
+    pub rings: isize,
+}
+
+
+
+impl SphereMeshFactory 
+{
+    pub fn new(radius: f32) -> Self
+    {
+        SphereMeshFactory {
+            radius,
+            segments: 24,
+            rings: 16,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for SphereMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Sphere {
+            radius: self.radius,
+            segments: self.segments,
+            rings: self.rings,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for SphereMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let mut vertex_data = Vec::new();
+        let mut index_data = Vec::new();
+
+        for i in 0..=self.rings 
+        {
+            let phi = (i as f32 / self.rings as f32) * std::f32::consts::PI;
+            let ring_radius = self.radius * phi.sin();
+            let z = self.radius * phi.cos();
+
+            for j in 0..=self.segments 
+            {
+                let theta = (j as f32 / self.segments as f32) * math::TWO_PI;
+
+                vertex_data.push(base_mesh_trait::Vertex::new([ ring_radius * theta.cos(),
+                                                                ring_radius * theta.sin(),
+                                                                z ]));
+            }
+        }
+
+        for i in 0..self.rings 
+        {
+            for j in 0..self.segments 
+            {
+                let row1 = i * (self.segments + 1);
+                let row2 = (i + 1) * (self.segments + 1);
+
+                let a = (row1 + j) as u32;
+                let b = (row2 + j) as u32;
+                let c = (row2 + j + 1) as u32;
+                let d = (row1 + j + 1) as u32;
+
+                index_data.extend_from_slice(&[a, b, c, c, d, a]);
+            }
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
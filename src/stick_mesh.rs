@@ -1,4 +1,4 @@
-use crate::{math, base_mesh_trait::{self, MeshFactory}};
+use crate::{math, base_mesh_trait::{self, IntoDesc, MeshFactory}, mesh_error::MeshError};
 
 
 
@@ -10,15 +10,33 @@ extern crate shader_version;
 
 
 
+// Stick along the Z axis centred at the origin, closed at the -Z end and rounded at the +Z one.
 pub struct StickMeshFactory
 {
+    pub radius: f32,
+    pub height: f32,
+    pub segments: isize,
+    pub height_segments: isize,
+    pub top_rings: isize,
 }
 
 impl StickMeshFactory 
 {
     pub fn new() -> Self
+    {
+        StickMeshFactory::sized(0.25, 15.0)
+    }
+
+
+
+    pub fn sized(radius: f32, height: f32) -> Self
     {
         StickMeshFactory {
+            radius,
+            height,
+            segments: 32,
+            height_segments: 10,
+            top_rings: 8,
         }
     }
 }
@@ -28,8 +46,12 @@ impl crate::base_mesh_trait::IntoDesc for StickMeshFactory
 {
     fn into_desc(&self) -> base_mesh_trait::MeshDesc 
     {
-        base_mesh_trait::MeshDesc { 
-            desc: ([ 0., 0., 0., 0. ]),
+        base_mesh_trait::MeshDesc::Stick {
+            radius: self.radius,
+            height: self.height,
+            segments: self.segments,
+            height_segments: self.height_segments,
+            top_rings: self.top_rings,
         }
     }
 }
@@ -37,56 +59,75 @@ impl crate::base_mesh_trait::IntoDesc for StickMeshFactory
 
 impl MeshFactory for StickMeshFactory 
 {
-    fn create_mesh_instance_on_gpu(&self,
-                                   open_gl: &crate::piston_window::OpenGL,
-                                   factory: &mut gfx_device_gl::Factory) -> crate::base_mesh_trait::BasicMeshOnGpu
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
     {
+        self.into_desc().validate()?;
+
         let mut vertex_data = Vec::new();
         let mut index_data = Vec::new();
 
-        let segments = 32; 
-        let height_segments = 10; 
-        let radius = 0.25;
-        let height = 15.0;
+        let segments = self.segments; 
+        let radius = self.radius;
+        let bottom = -self.height / 2.0;
+        // The rounded top is a half sphere, the straight part ends one radius below the tip.
+        let shoulder = self.height / 2.0 - radius.min(self.height);
+
+        // Rings of the straight part followed by the rings of the half sphere, the last one
+        // shrinks to the tip.
+        let mut rings = Vec::new();
+
+        for i in 0..=self.height_segments 
+        {
+            rings.push((radius, bottom + (i as f32 / self.height_segments as f32) * (shoulder - bottom)));
+        }
 
-        for i in 0..=height_segments 
+        for i in 1..=self.top_rings 
         {
-            let z = -height / 2.0 + (i as f32 / height_segments as f32) * height;
+            let phi = (i as f32 / self.top_rings as f32) * std::f32::consts::FRAC_PI_2;
+
+            rings.push((radius * phi.cos(), shoulder + radius * phi.sin()));
+        }
 
+        for (ring_radius, z) in &rings
+        {
             for j in 0..=segments 
             {
                 let theta = (j as f32 / segments as f32) * math::TWO_PI;
                 let cos_theta = theta.cos();
                 let sin_theta = theta.sin();
 
-                let x = radius * cos_theta;
-                let y = radius * sin_theta;
+                let x = ring_radius * cos_theta;
+                let y = ring_radius * sin_theta;
 
-                vertex_data.push(base_mesh_trait::Vertex::new([x, y, z]));
+                vertex_data.push(base_mesh_trait::Vertex::new([x, y, *z]));
             }
         }
 
-        for i in 0..height_segments 
+        for i in 0..rings.len() as isize - 1 
         {
             for j in 0..segments 
             {
                 let row1 = i * (segments + 1);
                 let row2 = (i + 1) * (segments + 1);
 
-                let a = (row1 + j) as u16;
-                let b = (row2 + j) as u16;
-                let c = (row2 + j + 1) as u16;
-                let d = (row1 + j + 1) as u16;
+                let a = (row1 + j) as u32;
+                let b = (row2 + j) as u32;
+                let c = (row2 + j + 1) as u32;
+                let d = (row1 + j + 1) as u32;
 
-                index_data.extend_from_slice(&[a, b, c, c, d, a]);
+                index_data.extend_from_slice(&[a, d, c, c, b, a]);
             }
         }
 
-        base_mesh_trait::BasicMeshOnGpu::new_from_vertices(open_gl, 
-                                                      factory,
-                                                      base_mesh_trait::pipe::new(),
-                                                      &vertex_data,
-                                                      &index_data)
+        // Flat cap closing the bottom ring.
+        let bottom_centre = vertex_data.len() as u32;
+        vertex_data.push(base_mesh_trait::Vertex::new([0., 0., bottom]));
+
+        for j in 0..segments 
+        {
+            index_data.extend_from_slice(&[bottom_centre, (j + 1) as u32, j as u32]);
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
     }
 }
-
@@ -0,0 +1,228 @@
+#![allow(dead_code)]
+
+use rand::Rng;
+
+pub type Peg = usize;
+
+pub const PEGS_AMOUNT: usize = 3;
+
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Disk
+{
+    pub width: i32,
+    // Colour group, disks of different groups are told apart even when they share a width.
+    pub group: u8,
+    // Which face of a magnetic disk points up, every disk starts with its north face up.
+    pub north_up: bool,
+}
+
+
+
+impl Disk
+{
+    pub fn new(width: i32) -> Self
+    {
+        Disk { width, group: 0, north_up: true }
+    }
+}
+
+
+
+/// Logical layout of the pegs, every peg is listed from the bottom disk to the top one.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TowerState
+{
+    pub pegs: [Vec<Disk>; PEGS_AMOUNT],
+}
+
+
+
+impl TowerState
+{
+    pub fn empty() -> Self
+    {
+        TowerState {
+            pegs: [ Vec::new(), Vec::new(), Vec::new() ],
+        }
+    }
+
+
+
+    pub fn tower(disks_amount: i32, peg: Peg) -> Self
+    {
+        let mut state = TowerState::empty();
+
+        for width in (1..=disks_amount).rev()
+        {
+            state.pegs[peg].push(Disk::new(width));
+        }
+
+        state
+    }
+
+
+
+    /// Tower of the given widths, widths may repeat.
+    pub fn from_sizes(sizes: &[i32], peg: Peg) -> Self
+    {
+        let mut state = TowerState::empty();
+        let mut sizes = sizes.to_vec();
+
+        sizes.sort_by_key(|width| -width);
+        state.pegs[peg] = sizes.into_iter().map(Disk::new).collect();
+
+        state
+    }
+
+
+
+    /// One tower where every width appears once per group, groups alternate from the bottom up.
+    pub fn interleaved(disks_amount: i32, groups: u8, peg: Peg) -> Self
+    {
+        let mut state = TowerState::empty();
+
+        for width in (1..=disks_amount).rev()
+        {
+            for group in 0..groups
+            {
+                state.pegs[peg].push(Disk { group, ..Disk::new(width) });
+            }
+        }
+
+        state
+    }
+
+
+
+    /// The same disks dropped on random sticks, every stick keeps the widths in order.
+    pub fn scrambled(&self) -> Self
+    {
+        let mut random = rand::thread_rng();
+        let mut state = TowerState::empty();
+        let mut disks: Vec<Disk> = self.pegs.iter().flatten().copied().collect();
+
+        disks.sort_by_key(|disk| -disk.width);
+
+        for disk in disks
+        {
+            state.pegs[random.gen_range(0..PEGS_AMOUNT)].push(disk);
+        }
+
+        state
+    }
+
+
+
+    /// Builds the state from `assignment[i]`, the peg of the disk with width `i + 1`.
+    pub fn from_assignment(assignment: &[Peg]) -> Self
+    {
+        let mut state = TowerState::empty();
+
+        for (i, peg) in assignment.iter().enumerate().rev()
+        {
+            state.pegs[*peg].push(Disk::new(i as i32 + 1));
+        }
+
+        state
+    }
+
+
+
+    pub fn assignment(&self) -> Vec<Peg>
+    {
+        let mut assignment = vec![0; self.disks_amount()];
+
+        for (peg, disks) in self.pegs.iter().enumerate()
+        {
+            for disk in disks
+            {
+                assignment[disk.width as usize - 1] = peg;
+            }
+        }
+
+        assignment
+    }
+
+
+
+    /// How many disks share each width, from the narrowest width up.
+    pub fn width_counts(&self) -> Vec<usize>
+    {
+        let mut widths: Vec<i32> = self.pegs.iter().flatten().map(|disk| disk.width).collect();
+        widths.sort();
+
+        widths.chunk_by(|a, b| a == b).map(|chunk| chunk.len()).collect()
+    }
+
+
+
+    pub fn disks_amount(&self) -> usize
+    {
+        self.pegs.iter().map(|peg| peg.len()).sum()
+    }
+
+
+
+    /// The stick holding every disk, if they all are stacked on one.
+    pub fn tower_peg(&self) -> Option<Peg>
+    {
+        let mut filled = self.pegs.iter().enumerate().filter(|(_, disks)| !disks.is_empty());
+
+        match (filled.next(), filled.next()) {
+            (Some((peg, _)), None) => Some(peg),
+            _ => None,
+        }
+    }
+
+
+
+    pub fn top(&self, peg: Peg) -> Option<&Disk>
+    {
+        self.pegs[peg].last()
+    }
+
+
+
+    pub fn move_disk(&mut self, from: Peg, to: Peg)
+    {
+        if let Some(disk) = self.pegs[from].pop() {
+            self.pegs[to].push(disk);
+        }
+    }
+
+
+
+    /// Compares the layouts while ignoring which face of every disk points up.
+    pub fn same_layout(&self, other: &TowerState) -> bool
+    {
+        self.pegs.iter()
+                 .zip(other.pegs.iter())
+                 .all(|(a, b)| a.iter().map(|disk| (disk.width, disk.group)).eq(b.iter().map(|disk| (disk.width, disk.group))))
+    }
+}
+
+
+
+impl std::fmt::Display for TowerState
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let grouped = self.pegs.iter().flatten().any(|disk| disk.group > 0);
+
+        for (peg, disks) in self.pegs.iter().enumerate()
+        {
+            let widths: Vec<String> = disks.iter()
+                                           .map(|disk| match grouped {
+                                               true => format!("{}{}", disk.width, (b'a' + disk.group) as char),
+                                               false => disk.width.to_string(),
+                                           })
+                                           .collect();
+            writeln!(f, "  stick {}: [{}]", peg + 1, widths.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
@@ -0,0 +1,117 @@
+#![allow(dead_code)]
+
+use vecmath::{Matrix4, Vector3};
+
+use crate::{math, quaternion::Quaternion};
+
+
+/// Placement of an actor: scaled along its own axes first, then rotated and moved.
+/// Composing and inverting are exact as long as the scales are uniform.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Transform
+{
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion,
+    pub scale: Vector3<f32>,
+}
+
+
+
+impl Transform
+{
+    pub fn identity() -> Self
+    {
+        Transform {
+            translation: [ 0., 0., 0. ],
+            rotation: Quaternion::identity(),
+            scale: [ 1., 1., 1. ],
+        }
+    }
+
+
+
+    pub fn new(translation: Vector3<f32>, rotation: Quaternion, scale: Vector3<f32>) -> Self
+    {
+        Transform { translation, rotation, scale }
+    }
+
+
+
+    /// Standing at `eye` with its -Z axis towards `target` and its Y axis as close to `up` as it gets,
+    /// the way cameras look.
+    pub fn look_at(eye: Vector3<f32>, target: Vector3<f32>, up: Vector3<f32>) -> Self
+    {
+        let z = vecmath::vec3_normalized(vecmath::vec3_sub(eye, target));
+        let x = vecmath::vec3_normalized(vecmath::vec3_cross(up, z));
+        let y = vecmath::vec3_cross(z, x);
+
+        let axes = [ [ x[0], x[1], x[2], 0. ],
+                     [ y[0], y[1], y[2], 0. ],
+                     [ z[0], z[1], z[2], 0. ],
+                     [ 0., 0., 0., 1. ] ];
+
+        Transform::new(eye, Quaternion::from_matrix(&axes), [ 1., 1., 1. ])
+    }
+
+
+
+    pub fn to_matrix(&self) -> Matrix4<f32>
+    {
+        let mut matrix = self.rotation.to_matrix();
+
+        math::scale_translation(&mut matrix, self.scale);
+        math::pos_translation(&mut matrix, self.translation);
+
+        matrix
+    }
+
+
+
+    pub fn transform_point(&self, point: Vector3<f32>) -> Vector3<f32>
+    {
+        vecmath::vec3_add(self.transform_vector(point), self.translation)
+    }
+
+
+
+    // Directions and offsets, they aren't moved.
+    pub fn transform_vector(&self, vector: Vector3<f32>) -> Vector3<f32>
+    {
+        self.rotation.rotate(vecmath::vec3_mul(vector, self.scale))
+    }
+
+
+
+    /// `child` placed inside `self`, like a node under its parent.
+    pub fn compose(&self, child: &Transform) -> Transform
+    {
+        Transform {
+            translation: self.transform_point(child.translation),
+            rotation: self.rotation * child.rotation,
+            scale: vecmath::vec3_mul(self.scale, child.scale),
+        }
+    }
+
+
+
+    pub fn inverse(&self) -> Transform
+    {
+        let rotation = self.rotation.conjugate();
+        let scale = [ 1. / self.scale[0], 1. / self.scale[1], 1. / self.scale[2] ];
+        let translation = vecmath::vec3_mul(rotation.rotate(self.translation), scale);
+
+        Transform::new(vecmath::vec3_neg(translation), rotation, scale)
+    }
+
+
+
+    /// From `self` at `t` = 0 to `other` at `t` = 1, the rotation turns at a constant speed.
+    pub fn slerp(&self, other: &Transform, t: f32) -> Transform
+    {
+        Transform {
+            translation: math::lerp(self.translation, other.translation, t),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: math::lerp(self.scale, other.scale, t),
+        }
+    }
+}
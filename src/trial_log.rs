@@ -0,0 +1,116 @@
+#![allow(dead_code)]
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    time::Duration,
+};
+
+
+const HEADER: &str = "trial,start,target,optimal_moves,budget,moves,invalid_moves,solved,planning_time_s,total_time_s";
+
+
+pub struct TrialRecord
+{
+    pub trial: u32,
+    pub start: String,
+    pub target: String,
+    pub optimal_moves: u32,
+    pub budget: u32,
+    pub moves: u32,
+    pub invalid_moves: u32,
+    pub solved: bool,
+
+    // From showing the target to the first move, `None` if no move was made.
+    pub planning_time: Option<Duration>,
+    pub total_time: Duration,
+}
+
+
+
+impl TrialRecord
+{
+    pub fn csv_line(&self) -> String
+    {
+        format!("{},{},{},{},{},{},{},{},{},{:.3}",
+                self.trial,
+                self.start,
+                self.target,
+                self.optimal_moves,
+                self.budget,
+                self.moves,
+                self.invalid_moves,
+                self.solved,
+                self.planning_time.map_or(String::new(), |time| format!("{:.3}", time.as_secs_f32())),
+                self.total_time.as_secs_f32())
+    }
+}
+
+
+
+impl std::fmt::Display for TrialRecord
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f,
+               "trial {}: {}, moves: {}/{} (optimal {}), invalid moves: {}, planning time: {}, time: {:.1}s",
+               self.trial,
+               if self.solved { "solved" } else { "failed" },
+               self.moves,
+               self.budget,
+               self.optimal_moves,
+               self.invalid_moves,
+               self.planning_time.map_or("-".to_string(), |time| format!("{:.1}s", time.as_secs_f32())),
+               self.total_time.as_secs_f32())
+    }
+}
+
+
+
+/// Appends one CSV line per trial, the header is written when the file is new.
+pub struct TrialLog
+{
+    file: Option<File>,
+}
+
+
+
+impl TrialLog
+{
+    pub fn open(path: &str) -> Self
+    {
+        let file = OpenOptions::new().create(true).append(true).open(path);
+
+        let file = match file
+        {
+            Ok(mut file) => {
+                if file.metadata().is_ok_and(|metadata| metadata.len() == 0) {
+                    let _ = writeln!(file, "{}", HEADER);
+                }
+
+                Some(file)
+            },
+            Err(error) => {
+                println!("Can't open the trial log {:?}, results are only printed: {}", path, error);
+                None
+            },
+        };
+
+        TrialLog { file }
+    }
+
+
+
+    pub fn write(&mut self, record: &TrialRecord)
+    {
+        println!("{}", record);
+
+        let Some(file) = &mut self.file else {
+            return;
+        };
+
+        if let Err(error) = writeln!(file, "{}", record.csv_line()) {
+            println!("Writing the trial log failed: {}", error);
+        }
+    }
+}
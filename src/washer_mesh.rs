@@ -0,0 +1,112 @@
+use crate::{math, base_mesh_trait::{self, IntoDesc}, mesh_error::MeshError};
+
+
+extern crate piston_window;
+extern crate vecmath;
+extern crate camera_controllers;
+extern crate gfx;
+extern crate shader_version;
+
+
+
+// Flat ring in the XY plane with bevelled edges, the flat alternative to the torus.
+pub struct WasherMeshFactory
+{
+    pub outer_radius: f32,
+    pub inner_radius: f32,
+    pub thickness: f32,
+    pub bevel: f32,
+    pub segments: isize,
+}
+
+
+
+impl WasherMeshFactory 
+{
+    pub fn new(outer_radius: f32, inner_radius: f32, thickness: f32) -> Self
+    {
+        WasherMeshFactory {
+            outer_radius,
+            inner_radius,
+            thickness,
+            bevel: thickness * 0.2,
+            segments: 32,
+        }
+    }
+}
+
+
+impl base_mesh_trait::IntoDesc for WasherMeshFactory
+{
+    fn into_desc(&self) -> base_mesh_trait::MeshDesc 
+    {
+        base_mesh_trait::MeshDesc::Washer {
+            outer_radius: self.outer_radius,
+            inner_radius: self.inner_radius,
+            thickness: self.thickness,
+            bevel: self.bevel,
+            segments: self.segments,
+        }
+    }
+}
+
+
+impl base_mesh_trait::MeshFactory for WasherMeshFactory 
+{
+    fn build_mesh(&self) -> Result<base_mesh_trait::MeshData, MeshError>
+    {
+        self.into_desc().validate()?;
+
+        let mut vertex_data = Vec::new();
+        let mut index_data = Vec::new();
+
+        let outer = self.outer_radius;
+        let inner = self.inner_radius;
+        let half = self.thickness * 0.5;
+        let bevel = self.bevel.min(half).min((outer - inner) * 0.5);
+
+        // Cross section of the ring as (distance from the axis, z), a rectangle with cut corners
+        // swept around the Z axis like the minor circle of the torus.
+        let profile = [ (outer - bevel, -half),
+                        (outer, -half + bevel),
+                        (outer, half - bevel),
+                        (outer - bevel, half),
+                        (inner + bevel, half),
+                        (inner, half - bevel),
+                        (inner, -half + bevel),
+                        (inner + bevel, -half) ];
+
+        let segments = self.segments;
+        let profile_len = profile.len() as isize;
+
+        for i in 0..segments 
+        {
+            let theta = (i as f32 / segments as f32) * math::TWO_PI;
+            let cos_theta = theta.cos();
+            let sin_theta = theta.sin();
+
+            for (distance, z) in profile
+            {
+                vertex_data.push(base_mesh_trait::Vertex::new([distance * cos_theta, distance * sin_theta, z]));
+            }
+        }
+
+        for i in 0..segments 
+        {
+            for j in 0..profile_len 
+            {
+                let next_i = (i + 1) % segments;
+                let next_j = (j + 1) % profile_len;
+
+                let a = (i * profile_len + j) as u32;
+                let b = (next_i * profile_len + j) as u32;
+                let c = (next_i * profile_len + next_j) as u32;
+                let d = (i * profile_len + next_j) as u32;
+
+                index_data.extend_from_slice(&[a, b, c, c, d, a]);
+            }
+        }
+
+        base_mesh_trait::MeshData::new(vertex_data, index_data)
+    }
+}
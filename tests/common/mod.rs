@@ -0,0 +1,27 @@
+// Helpers shared by the integration tests, each test file only uses some of them.
+#![allow(dead_code)]
+
+use vecmath::{Matrix4, Vector3};
+
+
+pub fn assert_near(a: Vector3<f32>, b: Vector3<f32>, tolerance: f32)
+{
+    assert!(a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < tolerance), "{:?} isn't {:?}", a, b);
+}
+
+
+
+pub fn assert_matrix_near(a: Matrix4<f32>, b: Matrix4<f32>)
+{
+    let near = a.iter().flatten().zip(b.iter().flatten()).all(|(a, b)| (a - b).abs() < 1e-4);
+
+    assert!(near, "{:?} isn't {:?}", a, b);
+}
+
+
+
+/// A file called `name` in the temporary directory, apart from other test runs.
+pub fn temp_path(name: &str) -> String
+{
+    std::env::temp_dir().join(format!("kolo_{}_{}", std::process::id(), name)).to_string_lossy().into_owned()
+}
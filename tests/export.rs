@@ -0,0 +1,192 @@
+mod common;
+
+use std::rc::Rc;
+
+use kolo::{
+    base_mesh_trait::{MeshData, MeshDesc},
+    mesh_export::{self, ExportedMesh},
+    model_mesh,
+};
+
+use common::temp_path;
+
+
+fn build(text: &str) -> Rc<MeshData>
+{
+    Rc::new(text.parse::<MeshDesc>().unwrap().factory().build_mesh().unwrap())
+}
+
+
+
+fn small_donut() -> Rc<MeshData>
+{
+    build("donut major_radius=1 minor_radius=0.35 segments_major=8 segments_minor=4")
+}
+
+
+
+fn small_stick() -> Rc<MeshData>
+{
+    build("stick radius=0.25 height=3 segments=6 height_segments=2 top_rings=2")
+}
+
+
+
+// The text after `"name": ` up to the end of the value, the exporter writes one JSON object per line.
+fn field<'a>(line: &'a str, name: &str) -> &'a str
+{
+    let start = line.find(&format!("\"{}\": ", name)).unwrap() + name.len() + 4;
+    let rest = &line[start..];
+    let end = match rest.as_bytes()[0] {
+        b'[' => rest.find(']').unwrap() + 1,
+        b'"' => rest[1..].find('"').unwrap() + 2,
+        _ => rest.find([ ',', ' ' ]).unwrap(),
+    };
+
+    &rest[..end]
+}
+
+
+
+fn lines_with<'a>(text: &'a str, pattern: &str) -> Vec<&'a str>
+{
+    text.lines().filter(|line| line.contains(pattern)).collect()
+}
+
+
+
+fn faces(obj: &str) -> Vec<[usize; 3]>
+{
+    obj.lines()
+       .filter_map(|line| line.strip_prefix("f "))
+       .map(|line| {
+           let indices: Vec<usize> = line.split(' ').map(|index| index.parse().unwrap()).collect();
+           [ indices[0], indices[1], indices[2] ]
+       })
+       .collect()
+}
+
+
+
+#[test]
+fn obj_has_every_vertex_and_triangle()
+{
+    let donut = small_donut();
+    let obj = mesh_export::mesh_to_obj(&donut, "donut");
+    let faces = faces(&obj);
+
+    assert_eq!(obj.lines().next(), Some("o donut"));
+    assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), donut.vertices.len());
+    assert_eq!(faces.len(), donut.indices.len() / 3);
+    assert_eq!(faces.iter().flatten().min(), Some(&1));
+    assert_eq!(faces.iter().flatten().max(), Some(&donut.vertices.len()));
+
+    // Read back it's the same mesh.
+    let path = temp_path("donut.obj");
+    std::fs::write(&path, &obj).unwrap();
+    let loaded = model_mesh::load_model(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded.indices, donut.indices);
+    assert!(loaded.vertices.iter().zip(donut.vertices.iter()).all(|(a, b)| a.a_pos == b.a_pos));
+}
+
+
+
+#[test]
+fn obj_scene_counts_indices_across_objects()
+{
+    let donut = small_donut();
+    let stick = small_stick();
+    let mut moved = vecmath::mat4_id();
+    moved[3] = [ 5., 0., 0., 1. ];
+
+    let scene = [ ExportedMesh { name: "donut".to_string(), mesh: donut.clone(), model: vecmath::mat4_id(), color: [ 1., 0., 0., 1. ] },
+                  ExportedMesh { name: "stick".to_string(), mesh: stick.clone(), model: moved, color: [ 0., 1., 0., 1. ] } ];
+    let obj = mesh_export::scene_to_obj(&scene);
+    let faces = faces(&obj);
+    let (donut_faces, stick_faces) = faces.split_at(donut.indices.len() / 3);
+
+    assert_eq!(lines_with(&obj, "o "), [ "o actor_0", "o actor_1" ]);
+    assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), donut.vertices.len() + stick.vertices.len());
+    assert_eq!(stick_faces.len(), stick.indices.len() / 3);
+    assert_eq!(donut_faces.iter().flatten().max(), Some(&donut.vertices.len()));
+    assert_eq!(stick_faces.iter().flatten().min(), Some(&(donut.vertices.len() + 1)));
+    assert_eq!(stick_faces.iter().flatten().max(), Some(&(donut.vertices.len() + stick.vertices.len())));
+
+    // The vertices are in world space with the actor colour after them.
+    let [x, y, z, _] = stick.vertices[0].a_pos;
+    let first_stick_vertex = obj.lines().filter(|line| line.starts_with("v ")).nth(donut.vertices.len()).unwrap();
+    assert_eq!(first_stick_vertex, format!("v {} {} {} 0 1 0", x + 5., y, z));
+}
+
+
+
+#[test]
+fn gltf_accessors_describe_the_buffer()
+{
+    let stick = small_stick();
+    let gltf = mesh_export::mesh_to_gltf(&stick, "stick");
+    let (min, max) = stick.bounds();
+
+    let accessors = lines_with(&gltf, "\"componentType\"");
+    assert_eq!(accessors.len(), 2);
+    assert_eq!(field(accessors[0], "count"), stick.vertices.len().to_string());
+    assert_eq!(field(accessors[0], "type"), "\"VEC3\"");
+    assert_eq!(field(accessors[0], "min"), format!("[ {}, {}, {} ]", min[0], min[1], min[2]));
+    assert_eq!(field(accessors[0], "max"), format!("[ {}, {}, {} ]", max[0], max[1], max[2]));
+    assert_eq!(field(accessors[1], "count"), stick.indices.len().to_string());
+    assert_eq!(field(accessors[1], "type"), "\"SCALAR\"");
+
+    // Three floats per vertex and one 32 bit integer per index, four base64 digits per three bytes.
+    let byte_length = stick.vertices.len() * 12 + stick.indices.len() * 4;
+    let buffer = lines_with(&gltf, "\"uri\"")[0];
+    let payload = field(buffer, "uri").trim_matches('"').strip_prefix("data:application/octet-stream;base64,").unwrap();
+
+    assert_eq!(field(buffer, "byteLength"), byte_length.to_string());
+    assert_eq!(payload.len(), byte_length.div_ceil(3) * 4);
+
+    let views = lines_with(&gltf, "\"byteOffset\"");
+    assert_eq!(field(views[1], "byteOffset"), (stick.vertices.len() * 12).to_string());
+    assert_eq!(field(views[1], "byteLength"), (stick.indices.len() * 4).to_string());
+
+    // Read back it's the same mesh.
+    let path = temp_path("stick.gltf");
+    std::fs::write(&path, &gltf).unwrap();
+    let loaded = model_mesh::load_model(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded.indices, stick.indices);
+    assert!(loaded.vertices.iter().zip(stick.vertices.iter()).all(|(a, b)| a.a_pos == b.a_pos));
+}
+
+
+
+#[test]
+fn gltf_scene_shares_the_geometry_of_one_mesh()
+{
+    let donut = small_donut();
+    let stick = small_stick();
+    let mut moved = vecmath::mat4_id();
+    moved[3] = [ 5., 0., 0., 1. ];
+
+    let actor = |mesh: &Rc<MeshData>, model, color| ExportedMesh { name: "actor".to_string(), mesh: mesh.clone(), model, color };
+    let scene = [ actor(&donut, vecmath::mat4_id(), [ 1., 0., 0., 1. ]),
+                  actor(&donut, moved, [ 1., 0., 0., 1. ]),
+                  actor(&donut, moved, [ 0., 0., 1., 1. ]),
+                  actor(&stick, moved, [ 0., 0., 1., 1. ]) ];
+    let gltf = mesh_export::scene_to_gltf(&scene);
+
+    let accessors = lines_with(&gltf, "\"componentType\"");
+    let counts: Vec<&str> = accessors.iter().map(|accessor| field(accessor, "count")).collect();
+    assert_eq!(counts, [ donut.vertices.len().to_string(), donut.indices.len().to_string(),
+                         stick.vertices.len().to_string(), stick.indices.len().to_string() ]);
+
+    assert_eq!(lines_with(&gltf, "\"baseColorFactor\"").len(), 2);
+    assert_eq!(lines_with(&gltf, "\"primitives\"").len(), 3);
+    assert_eq!(lines_with(&gltf, "\"matrix\"").len(), 4);
+    assert!(gltf.contains("\"nodes\": [ 0, 1, 2, 3 ]"));
+
+    let byte_length = (donut.vertices.len() + stick.vertices.len()) * 12 + (donut.indices.len() + stick.indices.len()) * 4;
+    assert_eq!(field(lines_with(&gltf, "\"uri\"")[0], "byteLength"), byte_length.to_string());
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use std::{collections::HashSet, time::Duration};
+
+use piston::{Button, Key};
+
+use kolo::{
+    game_settings::{GameMode, GameSettings},
+    london_master::LondonMaster,
+    london_rules::LondonRules,
+    master_trait::Master,
+    renderer::Renderer,
+    rule_set_trait::RuleSet,
+    tower_state::PEGS_AMOUNT,
+    trial_log::{TrialLog, TrialRecord},
+};
+
+use common::temp_path;
+
+
+fn log_path(name: &str) -> String
+{
+    let path = temp_path(&format!("{}.csv", name));
+    let _ = std::fs::remove_file(&path);
+
+    path
+}
+
+
+
+fn press(master: &mut LondonMaster, key: Key)
+{
+    master.update(Some(Button::Keyboard(key)));
+
+    while master.is_busy()
+    {
+        master.update(None);
+    }
+}
+
+
+
+#[test]
+fn pegs_hold_only_as_many_balls_as_they_fit()
+{
+    let rules = LondonRules::new();
+    let start = rules.start_state(&GameSettings::new());
+
+    assert!(rules.is_move_legal(&start, 0, 2));
+    assert!(!rules.is_move_legal(&start, 2, 0));
+
+    let moved = rules.try_move(&start, 0, 2).unwrap();
+    assert!(!rules.is_move_legal(&moved, 1, 2));
+    assert!(rules.is_move_legal(&moved, 1, 0));
+
+    // Every layout the rules lead to respects the capacities, there are 36 of them.
+    let mut seen = HashSet::from([ start.clone() ]);
+    let mut queue = vec![ start ];
+
+    while let Some(state) = queue.pop()
+    {
+        for (peg, balls) in state.pegs.iter().enumerate()
+        {
+            assert!(balls.len() <= rules.capacities[peg], "{:?}", state);
+        }
+
+        for (from, to) in (0..PEGS_AMOUNT).flat_map(|from| (0..PEGS_AMOUNT).map(move |to| (from, to)))
+        {
+            if let Some(next) = rules.try_move(&state, from, to).filter(|next| seen.insert(next.clone())) {
+                queue.push(next);
+            }
+        }
+    }
+
+    assert_eq!(seen.len(), 36);
+}
+
+
+
+#[test]
+fn trial_records_are_written_as_csv()
+{
+    let mut record = TrialRecord {
+        trial: 3,
+        start: LondonRules::layout_name(&LondonRules::new().start_state(&GameSettings::new())),
+        target: "R/G/B".to_string(),
+        optimal_moves: 4,
+        budget: 5,
+        moves: 6,
+        invalid_moves: 1,
+        solved: false,
+        planning_time: Some(Duration::from_millis(1500)),
+        total_time: Duration::from_millis(12250),
+    };
+
+    assert_eq!(record.csv_line(), "3,RG/B/,R/G/B,4,5,6,1,false,1.500,12.250");
+
+    record.planning_time = None;
+    assert_eq!(record.csv_line(), "3,RG/B/,R/G/B,4,5,6,1,false,,12.250");
+
+    // The header is only written into a new file.
+    let path = log_path("trial_log");
+    TrialLog::open(&path).write(&record);
+    TrialLog::open(&path).write(&record);
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "trial,start,target,optimal_moves,budget,moves,invalid_moves,solved,planning_time_s,total_time_s");
+    assert_eq!(lines[1], record.csv_line());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+
+
+#[test]
+fn trial_ends_when_the_move_budget_is_used_up()
+{
+    let mut settings = GameSettings::new();
+    settings.mode = GameMode::London;
+    settings.trials = 1;
+    settings.puzzle_distance = Some(2);
+    settings.trial_log = log_path("budget");
+
+    let mut renderer = Renderer::new();
+    let mut master = LondonMaster::new();
+    master.initialize(&settings, &mut renderer).unwrap();
+    let start = master.current_state();
+
+    // Nothing is on the third peg, the invalid move doesn't end the planning time.
+    press(&mut master, Key::D3);
+    press(&mut master, Key::D1);
+    std::thread::sleep(Duration::from_millis(50));
+
+    // Taking a ball away and back spends the whole budget of two moves without reaching the target.
+    for key in [ Key::D1, Key::D3, Key::D3, Key::D1 ]
+    {
+        assert!(master.is_playing());
+        press(&mut master, key);
+    }
+
+    assert_eq!(master.current_state(), start);
+    master.update(None);
+    assert!(!master.is_playing());
+
+    let text = std::fs::read_to_string(&settings.trial_log).unwrap();
+    let fields: Vec<&str> = text.lines().nth(1).unwrap().split(',').collect();
+
+    assert_eq!(fields[3..8], [ "2", "2", "2", "1", "false" ]);
+
+    let planning_time: f32 = fields[8].parse().unwrap();
+    let total_time: f32 = fields[9].parse().unwrap();
+    assert!(planning_time >= 0.05 && planning_time <= total_time, "{} of {}", planning_time, total_time);
+
+    let _ = std::fs::remove_file(&settings.trial_log);
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use std::f32::consts::PI;
+
+use camera_controllers::CameraPerspective;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vecmath::{Matrix4, Vector3};
+
+use kolo::{
+    math::{self, Ray},
+    quaternion::Quaternion,
+    transform::Transform,
+};
+
+use common::{assert_matrix_near, assert_near};
+
+
+// Every property is checked on this many random cases, seeded so a failure can be repeated.
+const CASES: usize = 500;
+
+
+
+fn cases(check: impl Fn(&mut StdRng))
+{
+    let mut rng = StdRng::seed_from_u64(49);
+
+    (0..CASES).for_each(|_| check(&mut rng));
+}
+
+
+
+fn vector(rng: &mut StdRng, extent: f32) -> Vector3<f32>
+{
+    [ rng.gen_range(-extent..extent), rng.gen_range(-extent..extent), rng.gen_range(-extent..extent) ]
+}
+
+
+
+// Long enough to be normalized without losing precision.
+fn direction(rng: &mut StdRng) -> Vector3<f32>
+{
+    loop
+    {
+        let v = vector(rng, 1.);
+
+        if vecmath::vec3_len(v) > 0.1 {
+            return vecmath::vec3_normalized(v);
+        }
+    }
+}
+
+
+
+fn point(matrix: Matrix4<f32>, p: Vector3<f32>) -> Vector3<f32>
+{
+    let [x, y, z, _] = vecmath::col_mat4_transform(matrix, [ p[0], p[1], p[2], 1. ]);
+
+    [ x, y, z ]
+}
+
+
+
+#[test]
+fn builders_move_points_like_vecmath()
+{
+    cases(|rng| {
+        let p = vector(rng, 50.);
+        let offset = vector(rng, 50.);
+        let scale = [ rng.gen_range(0.1..5.), rng.gen_range(0.1..5.), rng.gen_range(0.1..5.) ];
+
+        assert_near(point(math::translation(offset), p), vecmath::vec3_add(p, offset), 1e-3);
+        assert_near(point(math::scaling(scale), p), vecmath::vec3_mul(p, scale), 1e-3);
+        assert_near(math::transform_direction(&math::translation(offset), p), p, 1e-6);
+
+        let inverse = vecmath::mat4_inv(math::translation(offset));
+        assert_matrix_near(inverse, math::translation(vecmath::vec3_neg(offset)));
+    });
+}
+
+
+
+#[test]
+fn scaling_keeps_the_rotation()
+{
+    cases(|rng| {
+        let rotation = math::rotation_axis(direction(rng), rng.gen_range(-PI..PI));
+        let scale = [ rng.gen_range(0.1..5.), rng.gen_range(0.1..5.), rng.gen_range(0.1..5.) ];
+
+        let mut scaled = rotation;
+        math::scale_translation(&mut scaled, scale);
+
+        assert_matrix_near(scaled, vecmath::col_mat4_mul(rotation, math::scaling(scale)));
+    });
+}
+
+
+
+#[test]
+fn axis_rotations_are_rigid_and_match_quaternions()
+{
+    cases(|rng| {
+        let axis = direction(rng);
+        let angle = rng.gen_range(-PI..PI);
+        let rotation = math::rotation_axis(axis, angle);
+
+        assert_matrix_near(vecmath::col_mat4_mul(rotation, vecmath::mat4_transposed(rotation)), vecmath::mat4_id());
+        assert!((vecmath::mat4_det(rotation) - 1.).abs() < 1e-4);
+        assert_near(point(rotation, axis), axis, 1e-5);
+        assert_matrix_near(rotation, Quaternion::from_axis_angle(axis, angle).to_matrix());
+    });
+
+    for angle in [ -2., 0.4, PI * 0.5 ]
+    {
+        assert_matrix_near(math::rotation_axis([ 1., 0., 0. ], -angle), math::rotation_x(angle));
+        assert_matrix_near(math::rotation_axis([ 0., 1., 0. ], -angle), math::rotation_y(angle));
+        assert_matrix_near(math::rotation_axis([ 0., 0., 1. ], -angle), math::rotation_z(angle));
+    }
+}
+
+
+
+#[test]
+fn look_at_undoes_the_camera_placement()
+{
+    cases(|rng| {
+        let eye = vector(rng, 30.);
+        let target = vecmath::vec3_add(eye, vecmath::vec3_scale(direction(rng), rng.gen_range(1.0..30.)));
+
+        // Looking nearly straight up or down leaves no sideways axis.
+        if vecmath::vec3_normalized(vecmath::vec3_sub(target, eye))[1].abs() > 0.99 {
+            return;
+        }
+
+        let view = math::look_at(eye, target, [ 0., 1., 0. ]);
+
+        let placement = Transform::look_at(eye, target, [ 0., 1., 0. ]).to_matrix();
+        assert_matrix_near(vecmath::mat4_inv(view), placement);
+
+        assert_near(point(view, eye), [ 0., 0., 0. ], 1e-3);
+        assert_near(point(view, target), [ 0., 0., -math::distance(eye, target) ], 1e-3);
+    });
+}
+
+
+
+#[test]
+fn projections_map_their_volume_onto_the_unit_cube()
+{
+    cases(|rng| {
+        let fov = rng.gen_range(20.0..120.0f32);
+        let aspect = rng.gen_range(0.5..2.5);
+        let near = rng.gen_range(0.05..2.);
+        let far = near + rng.gen_range(1.0..500.);
+
+        let projection = math::perspective(fov.to_radians(), aspect, near, far);
+        let camera = CameraPerspective { fov, near_clip: near, far_clip: far, aspect_ratio: aspect };
+        let expected = camera.projection();
+        assert!(projection.iter().flatten().zip(expected.iter().flatten()).all(|(a, b)| (a - b).abs() <= 1e-4 * b.abs().max(1.)));
+
+        assert!((math::transform_point(&projection, [ 0., 0., -near ])[2] + 1.).abs() < 1e-3);
+        assert!((math::transform_point(&projection, [ 0., 0., -far ])[2] - 1.).abs() < 1e-3);
+
+        let [left, bottom] = [ rng.gen_range(-50.0..0.), rng.gen_range(-50.0..0.) ];
+        let [right, top] = [ left + rng.gen_range(1.0..50.), bottom + rng.gen_range(1.0..50.) ];
+        let orthographic = math::orthographic(left, right, bottom, top, near, far);
+
+        assert_near(math::transform_point(&orthographic, [ left, bottom, -near ]), [ -1., -1., -1. ], 1e-3);
+        assert_near(math::transform_point(&orthographic, [ right, top, -far ]), [ 1., 1., 1. ], 1e-3);
+    });
+}
+
+
+
+#[test]
+fn unproject_undoes_the_projection()
+{
+    cases(|rng| {
+        let view = math::look_at(vector(rng, 20.), vector(rng, 20.), [ 0., 1., 0. ]);
+        let projection = math::perspective(rng.gen_range(0.5..2.), rng.gen_range(0.5..2.5), 0.5, 100.);
+        let view_proj = vecmath::col_mat4_mul(projection, view);
+
+        let ndc = [ rng.gen_range(-1.0..1.), rng.gen_range(-1.0..1.), rng.gen_range(-0.9..0.9) ];
+        let world = math::unproject(ndc, &view_proj);
+
+        assert_near(math::transform_point(&view_proj, world), ndc, 1e-3);
+    });
+
+    // The middle of the window looks straight ahead.
+    let view_proj = vecmath::col_mat4_mul(math::perspective(1., 1.5, 0.1, 100.), math::look_at([ 0., 5., 10. ], [ 0., 5., 0. ], [ 0., 1., 0. ]));
+    let ray = math::pick_ray([ 320., 240. ], [ 640., 480. ], &view_proj);
+    assert_near(ray.direction, [ 0., 0., -1. ], 1e-4);
+    assert_near(ray.origin, [ 0., 5., 9.9 ], 1e-3);
+}
+
+
+
+#[test]
+fn rays_hit_planes_spheres_and_boxes()
+{
+    cases(|rng| {
+        let center = vector(rng, 20.);
+        let size = rng.gen_range(0.5..5.);
+        let origin = vecmath::vec3_add(center, vecmath::vec3_scale(direction(rng), rng.gen_range(10.0..40.)));
+
+        // Aimed somewhere inside, both shapes get hit no further away than that point.
+        let inside = vecmath::vec3_add(center, vecmath::vec3_scale(direction(rng), size * 0.5));
+        let ray = Ray::new(origin, vecmath::vec3_sub(inside, origin));
+        let reach = math::distance(origin, inside);
+
+        let hit = ray.hit_sphere(center, size).expect("aimed at the sphere");
+        assert!(hit <= reach + 1e-3);
+        assert!((math::distance(ray.at(hit), center) - size).abs() < 1e-3);
+
+        let min = vecmath::vec3_sub(center, [ size, size, size ]);
+        let max = vecmath::vec3_add(center, [ size, size, size ]);
+        let hit = ray.hit_aabb(min, max).expect("aimed at the box");
+        assert!(hit <= reach + 1e-3);
+        assert_near(math::max(math::min(ray.at(hit), max), min), ray.at(hit), 1e-3);
+
+        // Turned away, nothing is hit.
+        let away = Ray::new(origin, vecmath::vec3_sub(origin, inside));
+        assert_eq!(away.hit_sphere(center, size), None);
+        assert_eq!(away.hit_aabb(min, max), None);
+
+        // Starting inside counts as hitting right away.
+        let from_inside = Ray::new(center, direction(rng));
+        assert_eq!(from_inside.hit_sphere(center, size), Some(0.));
+        assert_eq!(from_inside.hit_aabb(min, max), Some(0.));
+
+        let normal = direction(rng);
+        match ray.hit_plane(center, normal) {
+            Some(hit) => assert!(vecmath::vec3_dot(vecmath::vec3_sub(ray.at(hit), center), normal).abs() < 1e-2),
+            None => assert!(vecmath::vec3_dot(vecmath::vec3_sub(center, origin), normal) * vecmath::vec3_dot(ray.direction, normal) <= 0.),
+        }
+    });
+
+    // Running along a slab outside the box misses it.
+    let ray = Ray::new([ -5., 2., 0. ], [ 1., 0., 0. ]);
+    assert_eq!(ray.hit_aabb([ -1., -1., -1. ], [ 1., 1., 1. ]), None);
+    assert_eq!(ray.hit_aabb([ -1., -3., -1. ], [ 1., 3., 1. ]), Some(4.));
+}
+
+
+
+#[test]
+fn vector_helpers()
+{
+    cases(|rng| {
+        let a = vector(rng, 10.);
+        let b = vector(rng, 10.);
+
+        assert_near(math::lerp(a, b, 0.), a, 1e-5);
+        assert_near(math::lerp(a, b, 1.), b, 1e-4);
+        assert!((math::distance(a, math::lerp(a, b, 0.25)) - math::distance(a, b) * 0.25).abs() < 1e-3);
+        assert_near(vecmath::vec3_add(math::min(a, b), math::max(a, b)), vecmath::vec3_add(a, b), 1e-4);
+    });
+}
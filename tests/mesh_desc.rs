@@ -0,0 +1,229 @@
+use kolo::{
+    base_mesh_trait::{self, MeshDesc},
+    mesh_error::MeshError,
+};
+
+
+fn desc(text: &str) -> MeshDesc
+{
+    text.parse().unwrap()
+}
+
+
+
+// Written without a wildcard, a new kind of mesh doesn't compile until it is listed here.
+fn variant(desc: &MeshDesc) -> usize
+{
+    match desc
+    {
+        MeshDesc::Donut { .. } => 0,
+        MeshDesc::Washer { .. } => 1,
+        MeshDesc::Stick { .. } => 2,
+        MeshDesc::Sphere { .. } => 3,
+        MeshDesc::Box { .. } => 4,
+        MeshDesc::Cylinder { .. } => 5,
+        MeshDesc::Cone { .. } => 6,
+        MeshDesc::Plane { .. } => 7,
+        MeshDesc::Arrow { .. } => 8,
+    }
+}
+
+
+
+#[test]
+fn sizes_match_the_built_meshes()
+{
+    for text in [ "donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=16",
+                  "washer outer_radius=1.5 inner_radius=0.4 thickness=0.6 bevel=0.1 segments=48",
+                  "stick radius=0.25 height=15 segments=32 height_segments=10 top_rings=8",
+                  "sphere radius=1.2 segments=24 rings=12",
+                  "box width=2 height=0.5 depth=3",
+                  "cylinder radius=0.5 height=2 segments=16 caps=true",
+                  "cylinder radius=0.5 height=2 segments=16 caps=false",
+                  "cone radius=0.75 height=1.5 segments=20",
+                  "plane width=40 depth=25.5 subdivisions=4",
+                  "arrow length=2 width=0.6 thickness=0.1" ]
+    {
+        let mesh = desc(text).factory().build_mesh().unwrap();
+
+        assert_eq!(desc(text).size(), (mesh.vertices.len(), mesh.indices.len()), "{}", text);
+    }
+}
+
+
+
+#[test]
+fn validate_rejects_bad_parameters()
+{
+    for text in [ "donut major_radius=-1 minor_radius=0.35 segments_major=32 segments_minor=16",
+                  "donut major_radius=NaN minor_radius=0.35 segments_major=32 segments_minor=16",
+                  "donut major_radius=1 minor_radius=inf segments_major=32 segments_minor=16",
+                  "donut major_radius=1 minor_radius=0.35 segments_major=2 segments_minor=16",
+                  "donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=4097",
+                  "washer outer_radius=1 inner_radius=1 thickness=0.6 bevel=0.1 segments=48",
+                  "washer outer_radius=1 inner_radius=-0.1 thickness=0.6 bevel=0.1 segments=48",
+                  "washer outer_radius=1 inner_radius=0.4 thickness=0.6 bevel=-0.1 segments=48",
+                  "stick radius=0.25 height=15 segments=32 height_segments=0 top_rings=8",
+                  "sphere radius=1 segments=24 rings=1",
+                  "box width=2 height=0 depth=3",
+                  "plane width=40 depth=25 subdivisions=0" ]
+    {
+        assert!(matches!(desc(text).validate(), Err(MeshError::InvalidParameter { .. })), "{}", text);
+    }
+}
+
+
+
+#[test]
+fn validate_caps_the_whole_mesh()
+{
+    // Every parameter is in range, together they ask for 16 million vertices.
+    let huge = desc("donut major_radius=1 minor_radius=0.35 segments_major=4096 segments_minor=4096");
+    assert!(matches!(huge.validate(), Err(MeshError::TooLarge { vertices: 16_777_216, .. })));
+    assert!(huge.factory().build_mesh().is_err());
+
+    assert!(desc("donut major_radius=1 minor_radius=0.35 segments_major=1024 segments_minor=1024").validate().is_ok());
+    assert!(desc("donut major_radius=1 minor_radius=0.35 segments_major=1025 segments_minor=1024").validate().is_err());
+    assert!(desc("plane width=1 depth=1 subdivisions=4096").validate().is_err());
+    assert!(desc("stick radius=1 height=1 segments=4096 height_segments=4096 top_rings=1").validate().is_err());
+
+    // Thin meshes stay well below the cap however long they get.
+    assert!(desc("cylinder radius=1 height=1 segments=4096 caps=true").validate().is_ok());
+    assert!(desc("washer outer_radius=1.5 inner_radius=0.4 thickness=0.6 bevel=0.1 segments=4096").validate().is_ok());
+}
+
+
+
+#[test]
+fn coarser_levels_keep_the_smallest_parameters()
+{
+    let stick = desc("stick radius=0.25 height=15 segments=32 height_segments=10 top_rings=8");
+    assert_eq!(stick.lod(0), stick);
+    assert_eq!(stick.lod(1), desc("stick radius=0.25 height=15 segments=16 height_segments=5 top_rings=4"));
+    assert_eq!(stick.lod(4), desc("stick radius=0.25 height=15 segments=3 height_segments=1 top_rings=1"));
+
+    // Halving stops at the least every kind needs, already coarse meshes stay as they are.
+    for (text, coarsest) in [ ("donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=16",
+                               "donut major_radius=1 minor_radius=0.35 segments_major=3 segments_minor=3"),
+                              ("washer outer_radius=1.5 inner_radius=0.4 thickness=0.6 bevel=0.1 segments=48",
+                               "washer outer_radius=1.5 inner_radius=0.4 thickness=0.6 bevel=0.1 segments=3"),
+                              ("sphere radius=1.2 segments=24 rings=12",
+                               "sphere radius=1.2 segments=3 rings=2"),
+                              ("cylinder radius=0.5 height=2 segments=3 caps=true",
+                               "cylinder radius=0.5 height=2 segments=3 caps=true"),
+                              ("cone radius=0.75 height=1.5 segments=20",
+                               "cone radius=0.75 height=1.5 segments=3"),
+                              ("plane width=40 depth=25.5 subdivisions=4",
+                               "plane width=40 depth=25.5 subdivisions=1"),
+                              ("box width=2 height=0.5 depth=3",
+                               "box width=2 height=0.5 depth=3"),
+                              ("arrow length=2 width=0.6 thickness=0.1",
+                               "arrow length=2 width=0.6 thickness=0.1") ]
+    {
+        assert_eq!(desc(text).lod(40), desc(coarsest), "{}", text);
+        assert!(desc(text).lod(40).validate().is_ok(), "{}", text);
+    }
+}
+
+
+
+#[test]
+fn normals_point_out_of_the_mesh()
+{
+    let sphere = desc("sphere radius=2 segments=24 rings=12").factory().build_mesh().unwrap();
+
+    for vertex in sphere.vertices.iter().filter(|vertex| vertex.a_pos[2].abs() < 1.9)
+    {
+        let outwards = vecmath::vec3_normalized([ vertex.a_pos[0], vertex.a_pos[1], vertex.a_pos[2] ]);
+
+        assert!(vecmath::vec3_dot(outwards, vertex.a_normal) > 0.99, "{:?}", vertex);
+    }
+
+    // The corners of a box are shared by three sides, their normals lean out of the corner.
+    let cube = desc("box width=2 height=2 depth=2").factory().build_mesh().unwrap();
+
+    for vertex in cube.vertices.iter()
+    {
+        let corner = vecmath::vec3_normalized([ vertex.a_pos[0], vertex.a_pos[1], vertex.a_pos[2] ]);
+
+        assert!(vecmath::vec3_dot(corner, vertex.a_normal) > 0.5, "{:?}", vertex);
+    }
+
+    for text in [ "stick radius=0.25 height=15 segments=32 height_segments=10 top_rings=8",
+                  "cylinder radius=0.5 height=2 segments=16 caps=true",
+                  "cone radius=0.75 height=1.5 segments=20",
+                  "arrow length=2 width=0.6 thickness=0.1" ]
+    {
+        let mesh = desc(text).factory().build_mesh().unwrap();
+        let (min, max) = mesh.bounds();
+        let center: [f32; 3] = std::array::from_fn(|axis| (min[axis] + max[axis]) * 0.5);
+
+        let inwards = mesh.vertices
+                          .iter()
+                          .filter(|vertex| vecmath::vec3_dot(vecmath::vec3_sub([ vertex.a_pos[0], vertex.a_pos[1], vertex.a_pos[2] ], center), vertex.a_normal) < 0.)
+                          .count();
+
+        assert!(inwards <= 1, "{} has {} normals pointing in", text, inwards);
+    }
+}
+
+
+
+#[test]
+fn grown_rings_keep_their_cross_section()
+{
+    let donut = desc("donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=16").factory().build_mesh().unwrap();
+    let grown = donut.grown(0.5);
+    let wider = desc("donut major_radius=1.5 minor_radius=0.35 segments_major=32 segments_minor=16").factory().build_mesh().unwrap();
+
+    for ((vertex, grown), wider) in donut.vertices.iter().zip(grown.vertices.iter()).zip(wider.vertices.iter())
+    {
+        assert!(grown.a_pos.iter().zip(wider.a_pos.iter()).all(|(a, b)| (a - b).abs() < 1e-5), "{:?} isn't {:?}", grown, wider);
+        assert_eq!(grown.a_normal, vertex.a_normal);
+    }
+
+    assert_eq!(base_mesh_trait::grow_ring([ 0., 0., 1., 1. ], 0.5), [ 0., 0., 1., 1. ]);
+    assert_eq!(base_mesh_trait::grow_ring([ 3., 4., 1., 1. ], 5.), [ 6., 8., 1., 1. ]);
+}
+
+
+
+#[test]
+fn mesh_descriptions_round_trip_through_text()
+{
+    let descs = [ "donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=16",
+                  "washer outer_radius=1.5 inner_radius=0.4 thickness=0.6 bevel=0.1 segments=48",
+                  "stick radius=0.25 height=15 segments=32 height_segments=10 top_rings=8",
+                  "sphere radius=1.2 segments=24 rings=12",
+                  "box width=2 height=0.5 depth=3",
+                  "cylinder radius=0.5 height=2 segments=16 caps=false",
+                  "cone radius=0.75 height=1.5 segments=20",
+                  "plane width=40 depth=25.5 subdivisions=4",
+                  "arrow length=2 width=0.6 thickness=0.1" ];
+    let mut seen = [ false; 9 ];
+
+    for text in descs
+    {
+        let desc: MeshDesc = text.parse().unwrap();
+
+        assert_eq!(desc.to_string(), text);
+        assert_eq!(desc.to_string().parse::<MeshDesc>(), Ok(desc));
+        assert_eq!(desc.kind(), text.split(' ').next().unwrap());
+        assert!(desc.factory().build_mesh().is_ok(), "{}", text);
+
+        seen[variant(&desc)] = true;
+    }
+
+    assert_eq!(seen, [ true; 9 ]);
+
+    assert!("donut major_radius=-1 minor_radius=0.35 segments_major=32 segments_minor=16".parse::<MeshDesc>()
+                                                                                      .unwrap()
+                                                                                      .validate()
+                                                                                      .is_err());
+
+    for text in [ "", "pyramid width=1", "box width=2 height=0.5", "box width=2 height=0.5 depth=3 depth=3",
+                  "box width=2 height=0.5 depth=3 colour=red", "box width=two height=0.5 depth=3", "box width" ]
+    {
+        assert!(text.parse::<MeshDesc>().is_err(), "{:?}", text);
+    }
+}
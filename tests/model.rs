@@ -0,0 +1,333 @@
+mod common;
+
+use kolo::{
+    base64,
+    base_mesh_trait::{MeshData, MeshDesc},
+    json::Json,
+    mesh_cache::MeshCache,
+    mesh_error::MeshError,
+    model_mesh,
+};
+
+use common::temp_path;
+
+
+const QUAD: [[f32; 3]; 4] = [ [ 0., 0., 0. ], [ 1., 0., 0. ], [ 1., 1., 0. ], [ 0., 1., 0. ] ];
+const QUAD_INDICES: [u32; 6] = [ 0, 1, 2, 2, 3, 0 ];
+
+// glTF index component types.
+const UNSIGNED_BYTE: u32 = 5121;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+
+
+// Writes the file, reads it as a model and removes it again.
+fn load(name: &str, bytes: &[u8]) -> Result<MeshData, MeshError>
+{
+    let path = temp_path(name);
+    std::fs::write(&path, bytes).unwrap();
+    let model = model_mesh::load_model(&path);
+    let _ = std::fs::remove_file(&path);
+
+    model
+}
+
+
+
+fn positions(mesh: &MeshData) -> Vec<[f32; 3]>
+{
+    mesh.vertices.iter().map(|vertex| [ vertex.a_pos[0], vertex.a_pos[1], vertex.a_pos[2] ]).collect()
+}
+
+
+
+// The quad positions followed by its indices stored with `index_type`.
+fn quad_buffer(index_type: u32) -> Vec<u8>
+{
+    let mut bytes: Vec<u8> = QUAD.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+
+    for index in QUAD_INDICES
+    {
+        match index_type {
+            UNSIGNED_BYTE => bytes.push(index as u8),
+            UNSIGNED_SHORT => bytes.extend((index as u16).to_le_bytes()),
+            _ => bytes.extend(index.to_le_bytes()),
+        }
+    }
+
+    bytes
+}
+
+
+
+// A glTF drawing the quad with the nodes given, `buffer` is the JSON of its only buffer.
+fn quad_gltf(index_type: u32, nodes: &str, buffer: &str) -> String
+{
+    let index_bytes = quad_buffer(index_type).len() - 48;
+
+    format!(r#"{{
+  "asset": {{ "version": "2.0" }},
+  "scene": 0,
+  "scenes": [ {{ "nodes": [ 0 ] }} ],
+  "nodes": {nodes},
+  "meshes": [ {{ "primitives": [ {{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }} ] }} ],
+  "accessors": [ {{ "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3" }},
+                 {{ "bufferView": 1, "componentType": {index_type}, "count": 6, "type": "SCALAR" }} ],
+  "bufferViews": [ {{ "buffer": 0, "byteLength": 48 }},
+                   {{ "buffer": 0, "byteOffset": 48, "byteLength": {index_bytes} }} ],
+  "buffers": [ {buffer} ]
+}}"#)
+}
+
+
+
+fn embedded_quad(index_type: u32, nodes: &str) -> String
+{
+    let buffer = quad_buffer(index_type);
+
+    quad_gltf(index_type,
+              nodes,
+              &format!(r#"{{ "byteLength": {}, "uri": "data:application/octet-stream;base64,{}" }}"#, buffer.len(), base64::encode(&buffer)))
+}
+
+
+
+fn glb(json: &str, binary: &[u8]) -> Vec<u8>
+{
+    let mut json = json.as_bytes().to_vec();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let mut binary = binary.to_vec();
+    binary.resize(binary.len().next_multiple_of(4), 0);
+
+    let length = 12 + 8 + json.len() + 8 + binary.len();
+    let mut bytes = Vec::new();
+
+    for word in [ 0x4654_6C67, 2, length as u32, json.len() as u32, 0x4E4F_534A ]
+    {
+        bytes.extend(u32::to_le_bytes(word));
+    }
+    bytes.extend(&json);
+
+    for word in [ binary.len() as u32, 0x004E_4942 ]
+    {
+        bytes.extend(u32::to_le_bytes(word));
+    }
+    bytes.extend(&binary);
+
+    bytes
+}
+
+
+
+#[test]
+fn json_values_are_parsed()
+{
+    let json = Json::parse(r#" { "name": "caf\u00e9\n", "list": [ 1, -2.5e1, true, false, null, [], {} ] } "#).unwrap();
+
+    assert_eq!(json.get("name").and_then(Json::as_str), Some("café\n"));
+    assert_eq!(json.get("list").and_then(|list| list.at(0)).and_then(Json::as_usize), Some(1));
+    assert_eq!(json.get("list").and_then(|list| list.at(1)).and_then(Json::as_f64), Some(-25.));
+    assert_eq!(json.get("list").and_then(|list| list.at(1)).and_then(Json::as_usize), None);
+    assert_eq!(json.get("list").and_then(Json::as_array).map(|list| &list[2..]),
+               Some(&[ Json::Bool(true), Json::Bool(false), Json::Null, Json::Array(Vec::new()), Json::Object(Vec::new()) ][..]));
+    assert_eq!(json.get("missing"), None);
+
+    for text in [ "", "{", "[ 1, ]", "[ 1 2 ]", "{ \"a\" 1 }", "{ 1: 2 }", "\"open", "\"\\u12\"", "tru", "1 2", "--1", "[ 1 ] ]" ]
+    {
+        assert!(Json::parse(text).is_err(), "{:?}", text);
+    }
+}
+
+
+
+#[test]
+fn json_nesting_is_limited()
+{
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    assert!(Json::parse(&nested(128)).is_ok());
+    assert!(Json::parse(&nested(129)).is_err());
+    assert!(Json::parse(&"{ \"a\": ".repeat(129)).is_err());
+
+    // Far too deep for the stack if it was followed.
+    assert!(Json::parse(&nested(1_000_000)).is_err());
+}
+
+
+
+#[test]
+fn base64_round_trips()
+{
+    for (bytes, text) in [ ("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy") ]
+    {
+        assert_eq!(base64::encode(bytes.as_bytes()), text);
+        assert_eq!(base64::decode(text), Some(bytes.as_bytes().to_vec()));
+    }
+
+    let bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(base64::decode(&base64::encode(&bytes)), Some(bytes));
+
+    assert_eq!(base64::decode("Zm9v\nYmFy"), Some(b"foobar".to_vec()));
+    assert_eq!(base64::decode("Zm9v!"), None);
+    assert_eq!(base64::decode("Zm9v-_"), None);
+}
+
+
+
+#[test]
+fn obj_faces_are_read_in_every_form()
+{
+    let obj = "# a quad, then two triangles
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+f 1/1/1 2/1/1 3/1/1 4/1/1
+v 0 0 1
+f -1 -4 -3
+f 1//1 2//1 5//1
+";
+    let model = load("faces.obj", obj.as_bytes()).unwrap();
+
+    assert_eq!(positions(&model)[4], [ 0., 0., 1. ]);
+    assert_eq!(model.indices, [ 0, 1, 2, 0, 2, 3, 4, 1, 2, 0, 1, 4 ]);
+
+    let triangle = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+    for text in [ "v 1 2\n".to_string(),
+                  "v 1 2 x\n".to_string(),
+                  format!("{}f 1 2\n", triangle),
+                  format!("{}f 0 1 2\n", triangle),
+                  format!("{}f 1 2 4\n", triangle),
+                  format!("{}f -4 1 2\n", triangle),
+                  format!("{}f 1 two 3\n", triangle),
+                  "v 1 1 1\nv 1 1 1\nv 1 1 1\nf 1 2 3\n".to_string(),
+                  triangle.to_string() ]
+    {
+        assert!(load("broken.obj", text.as_bytes()).is_err(), "{:?}", text);
+    }
+
+    assert!(matches!(load("model.stl", b"solid"), Err(MeshError::InvalidModel { .. })));
+    assert!(matches!(model_mesh::load_model(&temp_path("missing.obj")), Err(MeshError::Io { .. })));
+}
+
+
+
+#[test]
+fn gltf_indices_of_every_size_are_read()
+{
+    for index_type in [ UNSIGNED_BYTE, UNSIGNED_SHORT, UNSIGNED_INT ]
+    {
+        let model = load("quad.gltf", embedded_quad(index_type, r#"[ { "mesh": 0 } ]"#).as_bytes()).unwrap();
+
+        assert_eq!(positions(&model), QUAD);
+        assert_eq!(model.indices, QUAD_INDICES, "component type {}", index_type);
+    }
+
+    // Without indices the positions are taken three by three.
+    let gltf = embedded_quad(UNSIGNED_SHORT, r#"[ { "mesh": 0 } ]"#).replace(r#", "indices": 1"#, "")
+                                                                   .replace(r#""count": 4"#, r#""count": 3"#);
+    assert_eq!(load("unindexed.gltf", gltf.as_bytes()).unwrap().indices, [ 0, 1, 2 ]);
+}
+
+
+
+#[test]
+fn gltf_nodes_place_their_meshes()
+{
+    // Scaled by 2, turned a quarter around Z and moved, through a child node.
+    let nodes = r#"[ { "translation": [ 1, 2, 3 ], "rotation": [ 0, 0, 0.70710677, 0.70710677 ], "scale": [ 2, 2, 2 ], "children": [ 1 ] },
+                     { "mesh": 0 } ]"#;
+    let model = load("trs.gltf", embedded_quad(UNSIGNED_SHORT, nodes).as_bytes()).unwrap();
+    let expected = [ [ 1., 2., 3. ], [ 1., 4., 3. ], [ -1., 4., 3. ], [ -1., 2., 3. ] ];
+
+    for (pos, expected) in positions(&model).iter().zip(expected.iter())
+    {
+        assert!(pos.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-5), "{:?} isn't {:?}", pos, expected);
+    }
+
+    let nodes = r#"[ { "matrix": [ 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 5, 0, 0, 1 ], "mesh": 0 } ]"#;
+    let model = load("matrix.gltf", embedded_quad(UNSIGNED_SHORT, nodes).as_bytes()).unwrap();
+    assert_eq!(positions(&model)[1], [ 6., 0., 0. ]);
+
+    for nodes in [ r#"[ { "mesh": 0, "children": [ 1 ] }, { "children": [ 0 ] } ]"#,
+                   r#"[ { "mesh": 0, "children": [ 7 ] } ]"#,
+                   r#"[ { "mesh": 0, "scale": [ 1, 2 ] } ]"#,
+                   r#"[ { "mesh": 3 } ]"# ]
+    {
+        assert!(load("nodes.gltf", embedded_quad(UNSIGNED_SHORT, nodes).as_bytes()).is_err(), "{}", nodes);
+    }
+}
+
+
+
+#[test]
+fn broken_gltf_buffers_are_errors()
+{
+    let gltf = embedded_quad(UNSIGNED_SHORT, r#"[ { "mesh": 0 } ]"#);
+
+    for broken in [ gltf.replace(r#""count": 6"#, r#""count": 7"#),
+                    gltf.replace(r#""count": 6"#, r#""count": 1e300"#),
+                    gltf.replace(r#""byteOffset": 48"#, r#""byteOffset": 18446744073709551615"#),
+                    gltf.replace(r#""buffer": 0, "byteOffset""#, r#""buffer": 1, "byteOffset""#),
+                    gltf.replace("base64,", "base64,!"),
+                    gltf.replace(r#""componentType": 5126"#, r#""componentType": 5123"#),
+                    gltf.replace("\"data:", "\"missing.bin\", \"unused\": \""),
+                    gltf[..gltf.len() - 2].to_string() ]
+    {
+        assert!(matches!(load("broken.gltf", broken.as_bytes()), Err(MeshError::InvalidModel { .. })), "{}", broken);
+    }
+}
+
+
+
+#[test]
+fn glb_binary_chunk_holds_the_buffer()
+{
+    let buffer = quad_buffer(UNSIGNED_INT);
+    let json = quad_gltf(UNSIGNED_INT, r#"[ { "mesh": 0 } ]"#, &format!(r#"{{ "byteLength": {} }}"#, buffer.len()));
+    let bytes = glb(&json, &buffer);
+
+    let model = load("quad.glb", &bytes).unwrap();
+    assert_eq!(positions(&model), QUAD);
+    assert_eq!(model.indices, QUAD_INDICES);
+
+    let mut wrong_magic = bytes.clone();
+    wrong_magic[3] = b'X';
+
+    for broken in [ &bytes[..bytes.len() - 4], &bytes[..10], &wrong_magic[..], &glb(&json, &[])[..] ]
+    {
+        assert!(load("broken.glb", broken).is_err());
+    }
+}
+
+
+
+#[test]
+fn models_are_read_once_for_every_size()
+{
+    let path = temp_path("cached.gltf");
+    std::fs::write(&path, embedded_quad(UNSIGNED_BYTE, r#"[ { "mesh": 0 } ]"#)).unwrap();
+
+    let mut cache = MeshCache::new();
+    cache.use_model(&[ "donut" ], &path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    // The file is gone, the meshes still come from the model.
+    let narrow: MeshDesc = "donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=16".parse().unwrap();
+    let wide: MeshDesc = "donut major_radius=2 minor_radius=0.35 segments_major=32 segments_minor=16".parse().unwrap();
+
+    let narrow = cache.mesh_data(&narrow).unwrap();
+    let wide = cache.mesh_data(&wide).unwrap();
+
+    assert_eq!(narrow.indices, QUAD_INDICES);
+    assert_eq!(wide.indices, QUAD_INDICES);
+    assert!((narrow.bounds().1[0] - narrow.bounds().0[0] - 2.7).abs() < 1e-5);
+    assert!((wide.bounds().1[0] - wide.bounds().0[0] - 4.7).abs() < 1e-5);
+
+    assert!(cache.has_model("donut"));
+    assert!(cache.use_model(&[ "stick" ], &path).is_err());
+    assert!(!cache.has_model("stick"));
+}
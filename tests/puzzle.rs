@@ -0,0 +1,362 @@
+use kolo::{
+    equal_size_rules::EqualSizeRules,
+    game_settings::GameSettings,
+    london_rules::LondonRules,
+    magnetic_rules::MagneticRules,
+    rule_set_registry::RuleSetRegistry,
+    rule_set_trait::RuleSet,
+    solver,
+    tower_state::TowerState,
+};
+
+
+fn settings(rules: &str, disks: i32) -> GameSettings
+{
+    let mut settings = GameSettings::new();
+    settings.dounuts_amount = disks;
+    settings.rules = RuleSetRegistry::with_builtin().get(rules).unwrap();
+
+    settings
+}
+
+
+
+// Plays the planned moves one by one, every one of them has to be legal.
+fn play(rules: &dyn RuleSet, from: &TowerState, to: &TowerState) -> (TowerState, usize)
+{
+    let moves = rules.plan_moves(from, to).unwrap();
+    let mut state = from.clone();
+
+    for game_move in moves.iter()
+    {
+        assert!(rules.is_move_legal(&state, game_move.from, game_move.to), "{} plays an illegal move", rules.name());
+        rules.apply_move(&mut state, game_move.from, game_move.to);
+    }
+
+    (state, moves.len())
+}
+
+
+
+#[test]
+fn classic_solution_is_optimal()
+{
+    let settings = settings("classic", 6);
+    let rules = settings.rules.as_ref();
+
+    let (state, moves) = play(rules, &rules.start_state(&settings), &rules.goal_state(&settings));
+
+    assert_eq!(moves, 63);
+    assert!(rules.is_won(&state, &rules.goal_state(&settings)));
+}
+
+
+
+#[test]
+fn every_builtin_rule_set_reaches_its_goal()
+{
+    let registry = RuleSetRegistry::with_builtin();
+
+    for name in registry.names()
+    {
+        let settings = settings(name, 3);
+        let rules = settings.rules.as_ref();
+        let goal = rules.goal_state(&settings);
+
+        let (state, _) = play(rules, &rules.start_state(&settings), &goal);
+
+        assert!(rules.is_won(&state, &goal), "{} doesn't reach the goal", name);
+    }
+}
+
+
+
+#[test]
+fn hanoi_targets_are_the_asked_distance_away()
+{
+    let settings = settings("classic", 6);
+    let rules = settings.rules.as_ref();
+    let start = rules.start_state(&settings);
+
+    for distance in 0..=63
+    {
+        let target = rules.random_target(&start, 0, distance);
+        let (state, moves) = play(rules, &start, &target);
+
+        assert_eq!(moves as u64, distance);
+        assert_eq!(solver::distance_to_tower(&target.assignment(), 0), distance);
+        assert_eq!(state, target);
+    }
+}
+
+
+
+#[test]
+fn huge_puzzles_saturate_the_distance()
+{
+    for disks in [ 63, 64, 80 ]
+    {
+        let assignment = solver::random_assignment_at_distance(disks, 0, u64::MAX);
+        let expected = if disks == 63 { (1 << 63) - 1 } else { u64::MAX };
+
+        assert_eq!(solver::distance_to_tower(&assignment, 0), expected);
+    }
+}
+
+
+
+#[test]
+fn distances_between_layouts_match_the_moves()
+{
+    // Every pair of layouts of four disks.
+    let layouts: Vec<Vec<usize>> = (0..81).map(|code: usize| (0..4).map(|disk| code / 3usize.pow(disk) % 3).collect()).collect();
+
+    for from in layouts.iter()
+    {
+        for to in layouts.iter()
+        {
+            let mut moves = std::collections::VecDeque::new();
+            solver::moves_between(&mut moves, from, to);
+
+            assert_eq!(solver::distance_between(from, to), moves.len() as u64, "{:?} to {:?}", from, to);
+        }
+    }
+
+    // Moving the 70th disk takes more moves than a u64 holds, the distance saturates instead of overflowing.
+    let from = vec![ 0; 70 ];
+    let mut to = from.clone();
+    to[69] = 1;
+    assert_eq!(solver::distance_between(&from, &to), u64::MAX);
+
+    to[69] = 0;
+    to[3] = 2;
+    assert_eq!(solver::distance_between(&from, &to), 15);
+}
+
+
+
+#[test]
+fn puzzle_distance_has_to_be_positive()
+{
+    let args = |distance: &str| [ "kolo", "--puzzle", distance ].map(String::from).into_iter();
+
+    assert_eq!(GameSettings::from_args(args("0")).puzzle_distance, None);
+    assert_eq!(GameSettings::from_args(args("12")).puzzle_distance, Some(12));
+}
+
+
+
+#[test]
+fn sizes_decide_the_amount_of_disks()
+{
+    let parse = |args: &[&str]| GameSettings::from_args([ "kolo" ].iter().chain(args).map(|arg| arg.to_string()));
+
+    assert_eq!(parse(&[ "--sizes", "1,1,2", "--disks", "8" ]).dounuts_amount, 3);
+    assert_eq!(parse(&[ "--disks", "8", "--sizes", "1,1,2" ]).dounuts_amount, 3);
+    assert_eq!(parse(&[ "--disks", "8" ]).dounuts_amount, 8);
+
+    // The colour variants bring their own tower.
+    let settings = parse(&[ "--rules", "bicolor", "--sizes", "1,1,2", "--disks", "4" ]);
+    assert_eq!(settings.disk_sizes, None);
+    assert_eq!(settings.dounuts_amount, 4);
+    assert_eq!(settings.rules.name(), "bicolor");
+}
+
+
+
+#[test]
+fn disks_are_capped_by_the_rules()
+{
+    let parse = |args: &[&str]| GameSettings::from_args([ "kolo" ].iter().chain(args).map(|arg| arg.to_string()));
+
+    assert_eq!(parse(&[ "--disks", "64" ]).dounuts_amount, 64);
+    assert_eq!(parse(&[ "--disks", "1000" ]).dounuts_amount, 64);
+    assert_eq!(parse(&[ "--rules", "cyclic", "--disks", "30" ]).dounuts_amount, 14);
+    assert_eq!(parse(&[ "--rules", "custom:12,21,23,32", "--disks", "30" ]).dounuts_amount, 13);
+
+    // The narrowest sizes are kept.
+    let sizes: Vec<String> = (1..=30).map(|size| ((size + 1) / 2).to_string()).collect();
+    let settings = parse(&[ "--sizes", &sizes.join(",") ]);
+    assert_eq!(settings.rules.name(), "equal-size");
+    assert_eq!(settings.dounuts_amount, 20);
+    assert_eq!(settings.disk_sizes, Some((1..=20).map(|size| (size + 1) / 2).collect()));
+}
+
+
+
+#[test]
+fn classic_next_moves_follow_an_optimal_plan()
+{
+    let rules = RuleSetRegistry::with_builtin().get("classic").unwrap();
+    let layouts: Vec<Vec<usize>> = (0..81).map(|code: usize| (0..4).map(|disk| code / 3usize.pow(disk) % 3).collect()).collect();
+
+    for from in layouts.iter().map(|layout| TowerState::from_assignment(layout))
+    {
+        for to in layouts.iter().map(|layout| TowerState::from_assignment(layout))
+        {
+            let distance = rules.distance(&from, &to).unwrap();
+            let moves = rules.next_moves(&from, &to, usize::MAX).unwrap();
+            let mut state = from.clone();
+
+            assert_eq!(moves.len() as u64, distance);
+            assert_eq!(rules.next_moves(&from, &to, 3).unwrap().len() as u64, distance.min(3));
+
+            for game_move in moves
+            {
+                assert!(rules.is_move_legal(&state, game_move.from, game_move.to));
+                rules.apply_move(&mut state, game_move.from, game_move.to);
+            }
+
+            assert_eq!(state, to);
+        }
+    }
+
+    // The biggest tower is only planned as far as asked.
+    let settings = settings("classic", 64);
+    let start = rules.start_state(&settings);
+    let goal = rules.goal_state(&settings);
+
+    assert_eq!(rules.distance(&start, &goal), Some(u64::MAX));
+    assert_eq!(rules.next_moves(&start, &goal, 1000).unwrap().len(), 1000);
+}
+
+
+
+#[test]
+fn colour_groups_are_sorted_onto_the_goal_stick()
+{
+    let parse = |args: &[&str]| GameSettings::from_args([ "kolo" ].iter().chain(args).map(|arg| arg.to_string()));
+    let groups_on = |settings: &GameSettings| -> Vec<Vec<u8>> {
+        let goal = settings.rules.goal_state(settings);
+        goal.pegs.iter().map(|disks| disks.iter().map(|disk| disk.group).take(1).collect()).collect()
+    };
+
+    let settings = parse(&[ "--rules", "bicolor", "--start", "1", "--goal", "2" ]);
+    assert_eq!(groups_on(&settings), [ vec![], vec![ 0 ], vec![ 1 ] ]);
+
+    let settings = parse(&[ "--rules", "bicolor", "--start", "2", "--goal", "3" ]);
+    assert_eq!(groups_on(&settings), [ vec![ 1 ], vec![], vec![ 0 ] ]);
+
+    let settings = parse(&[ "--rules", "tricolor", "--start", "3", "--goal", "1" ]);
+    assert_eq!(groups_on(&settings), [ vec![ 0 ], vec![ 1 ], vec![ 2 ] ]);
+
+    // Sorting the tower is a game even when it starts on the goal stick.
+    let settings = parse(&[ "--rules", "bicolor", "--start", "2", "--goal", "2" ]);
+    assert_eq!(groups_on(&settings), [ vec![], vec![ 0 ], vec![ 1 ] ]);
+    assert_ne!(settings.rules.start_state(&settings), settings.rules.goal_state(&settings));
+}
+
+
+
+#[test]
+fn grouped_solutions_are_as_short_as_the_search_finds()
+{
+    let rules = EqualSizeRules;
+
+    for sizes in [ vec![ 1, 1 ], vec![ 1, 1, 2 ], vec![ 1, 2, 2, 3 ], vec![ 1, 1, 1, 2, 3, 3 ], vec![ 1, 2, 2, 2, 3, 4, 4 ] ]
+    {
+        let from = TowerState::from_sizes(&sizes, 0);
+        let to = TowerState::from_sizes(&sizes, 2);
+
+        let searched = solver::moves_by_search(&from,
+                                               |state| rules.is_won(state, &to),
+                                               |state, a, b| rules.try_move(state, a, b)).unwrap();
+        let (state, moves) = play(&rules, &from, &to);
+
+        assert!(rules.is_won(&state, &to));
+        assert_eq!(moves, searched.len(), "sizes {:?}", sizes);
+    }
+}
+
+
+
+#[test]
+fn colour_sorting_is_as_short_as_the_search_finds()
+{
+    for (name, widths) in [ ("bicolor", 1..=4), ("tricolor", 1..=2) ]
+    {
+        for disks in widths
+        {
+            let settings = settings(name, disks);
+            let rules = settings.rules.clone();
+            let from = rules.start_state(&settings);
+            let to = rules.goal_state(&settings);
+
+            let searched = solver::moves_by_search(&from,
+                                                   |state| rules.is_won(state, &to),
+                                                   |state, a, b| rules.try_move(state, a, b)).unwrap();
+            let (state, moves) = play(rules.as_ref(), &from, &to);
+
+            assert!(rules.is_won(&state, &to));
+            assert_eq!(moves, searched.len(), "{} with {} widths", name, disks);
+        }
+    }
+
+    // Bigger towers are sorted a width at a time.
+    for (name, disks) in [ ("bicolor", 8), ("tricolor", 6) ]
+    {
+        let settings = settings(name, disks);
+        let rules = settings.rules.as_ref();
+        let goal = rules.goal_state(&settings);
+
+        let (state, _) = play(rules, &rules.start_state(&settings), &goal);
+
+        assert!(rules.is_won(&state, &goal), "{} with {} widths", name, disks);
+    }
+}
+
+
+
+#[test]
+fn magnetic_solutions_are_as_short_as_the_search_finds()
+{
+    let rules = MagneticRules;
+    let try_move = |state: &TowerState, from, to| rules.try_move(state, from, to);
+
+    for disks in 1..=5
+    {
+        for distance in [ 0, 3, 7, 12, 20 ]
+        {
+            let from = solver::random_state_by_search(&TowerState::tower(disks, 0), distance, try_move);
+            let to = solver::random_state_by_search(&TowerState::tower(disks, 2), distance, try_move);
+
+            let searched = solver::moves_by_search(&from, |state| rules.is_won(state, &to), try_move).unwrap();
+            let (state, moves) = play(&rules, &from, &to);
+
+            assert!(rules.is_won(&state, &to));
+            assert_eq!(moves, searched.len(), "{} disks from {:?} to {:?}", disks, from, to);
+        }
+    }
+}
+
+
+
+#[test]
+fn magnetic_towers_are_solved_beyond_the_search()
+{
+    let settings = settings("magnetic", 11);
+    let rules = settings.rules.as_ref();
+    let goal = rules.goal_state(&settings);
+
+    let (state, _) = play(rules, &rules.start_state(&settings), &goal);
+
+    assert!(rules.is_won(&state, &goal));
+}
+
+
+
+#[test]
+fn london_targets_are_the_asked_distance_away()
+{
+    let rules = LondonRules::new();
+    let start = rules.start_state(&GameSettings::new());
+
+    for distance in 0..=6
+    {
+        let target = rules.random_target(&start, 0, distance);
+        let (state, moves) = play(&rules, &start, &target);
+
+        assert_eq!(moves as u64, distance);
+        assert_eq!(state, target);
+    }
+}
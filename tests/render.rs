@@ -0,0 +1,174 @@
+use piston::{Button, Key};
+
+use kolo::{
+    actor_trait::ActorBase,
+    base_mesh_trait::MeshDesc,
+    game_master::GameMaster,
+    game_settings::GameSettings,
+    master_trait::Master,
+    recording_backend::{RecordedFrame, RecordingBackend},
+    renderer::Renderer,
+};
+
+
+// Roughly where the game camera looks from, with a 60 degree view.
+const CAMERA: [[f32; 4]; 4] = [ [ 1., 0., 0., 0. ],
+                                [ 0., 1., 0., 0. ],
+                                [ 0., 0., 1., 0. ],
+                                [ -0.5, -10.5, -21., 1. ] ];
+const PROJECTION: [[f32; 4]; 4] = [ [ 1.3, 0., 0., 0. ],
+                                    [ 0., 1.73, 0., 0. ],
+                                    [ 0., 0., -1., -1. ],
+                                    [ 0., 0., -0.2, 0. ] ];
+
+
+fn start_game(disks: i32) -> (GameMaster, Renderer)
+{
+    let mut settings = GameSettings::new();
+    settings.dounuts_amount = disks;
+
+    let mut renderer = Renderer::new();
+    let mut master = GameMaster::new();
+    master.initialize(&settings, &mut renderer).unwrap();
+    master.update(None);
+
+    (master, renderer)
+}
+
+
+
+fn draw(master: &mut GameMaster, renderer: &mut Renderer, backend: &mut RecordingBackend) -> RecordedFrame
+{
+    renderer.begin(&CAMERA, &PROJECTION);
+    master.render(renderer);
+    renderer.submit(backend);
+
+    backend.last_frame().unwrap().clone()
+}
+
+
+
+// Translations of every instance drawn with a mesh of `kind`, rounded to centimetres.
+fn positions(frame: &RecordedFrame, renderer: &Renderer, kind: &str) -> Vec<[i32; 3]>
+{
+    let mut positions: Vec<[i32; 3]> = frame.calls
+                                            .iter()
+                                            .filter(|call| renderer.cache().get(call.mesh).name.starts_with(kind))
+                                            .flat_map(|call| call.instances.iter())
+                                            .map(|instance| {
+                                                let pos = instance.i_model_3;
+                                                [ (pos[0] * 100.).round() as i32, (pos[1] * 100.).round() as i32, (pos[2] * 100.).round() as i32 ]
+                                            })
+                                            .collect();
+    positions.sort();
+
+    positions
+}
+
+
+
+#[test]
+fn every_actor_is_drawn_once_per_frame()
+{
+    let (mut master, mut renderer) = start_game(4);
+    let mut backend = RecordingBackend::new([ 640, 480 ]);
+
+    let frame = draw(&mut master, &mut renderer, &mut backend);
+    let instances: usize = frame.calls.iter().map(|call| call.instances.len()).sum();
+
+    assert_eq!(backend.frames.len(), 1);
+    assert_eq!(frame.calls.len(), renderer.draw_calls());
+    assert_eq!(instances, 4 + 3);
+    assert_eq!(positions(&frame, &renderer, "stick"), [ [ -1500, 100, -1200 ], [ 0, 100, -1000 ], [ 1500, 100, -1200 ] ]);
+    assert_eq!(frame.view_proj, vecmath::col_mat4_mul(PROJECTION, CAMERA));
+
+    // Every mesh and level of detail is drawn with one call.
+    let mut meshes: Vec<usize> = frame.calls.iter().map(|call| call.mesh.0).collect();
+    meshes.sort();
+    meshes.dedup();
+    assert_eq!(meshes.len(), frame.calls.len());
+}
+
+
+
+#[test]
+fn disks_of_every_width_share_one_draw_call()
+{
+    let (mut master, mut renderer) = start_game(6);
+    let mut backend = RecordingBackend::new([ 640, 480 ]);
+
+    let frame = draw(&mut master, &mut renderer, &mut backend);
+    let disk_calls: Vec<_> = frame.calls
+                                  .iter()
+                                  .filter(|call| renderer.cache().get(call.mesh).name.starts_with("donut"))
+                                  .collect();
+
+    assert_eq!(disk_calls.len(), 1);
+
+    // The narrowest mesh is widened by 0.2 per width.
+    let mut growths: Vec<i32> = disk_calls[0].instances.iter().map(|instance| (instance.i_ring_growth * 10.).round() as i32).collect();
+    growths.sort();
+    assert_eq!(growths, [ 0, 2, 4, 6, 8, 10 ]);
+}
+
+
+
+#[test]
+fn moved_disk_is_drawn_on_the_target_stick()
+{
+    let (mut master, mut renderer) = start_game(3);
+    let mut backend = RecordingBackend::new([ 640, 480 ]);
+
+    master.update(Some(Button::Keyboard(Key::D1)));
+    master.update(Some(Button::Keyboard(Key::D3)));
+
+    for _ in 0..200
+    {
+        master.update(None);
+    }
+
+    let frame = draw(&mut master, &mut renderer, &mut backend);
+    let donuts = positions(&frame, &renderer, "donut");
+
+    assert_eq!(master.current_state().pegs[2].len(), 1);
+    assert_eq!(donuts, [ [ -1500, -600, -1200 ], [ -1500, -540, -1200 ], [ 1500, -600, -1200 ] ]);
+}
+
+
+
+#[test]
+fn distant_meshes_are_drawn_coarser()
+{
+    let mut renderer = Renderer::new();
+    let mut backend = RecordingBackend::new([ 640, 480 ]);
+
+    let donut: MeshDesc = "donut major_radius=1 minor_radius=0.35 segments_major=32 segments_minor=16".parse().unwrap();
+    let stick: MeshDesc = "stick radius=0.25 height=3 segments=32 height_segments=10 top_rings=8".parse().unwrap();
+
+    for desc in [ donut, stick ]
+    {
+        let mesh = renderer.mesh(&desc).unwrap();
+        let levels = renderer.cache().lods(mesh);
+        let vertices: Vec<usize> = levels.iter().map(|level| renderer.cache().get(*level).data.vertices.len()).collect();
+
+        assert_eq!(levels.len(), 3, "{}", desc);
+        assert!(vertices.windows(2).all(|pair| pair[0] > pair[1]), "{} has {:?} vertices", desc, vertices);
+
+        // Close up it fills the screen, far away it's a few pixels.
+        let actor = ActorBase::new(mesh);
+
+        for (distance, level) in [ (3., 0), (15., 1), (1000., 2) ]
+        {
+            let mut camera = vecmath::mat4_id();
+            camera[3][2] = -distance;
+
+            renderer.begin(&camera, &PROJECTION);
+            renderer.push(&actor);
+            renderer.submit(&mut backend);
+
+            let frame = backend.last_frame().unwrap();
+            assert_eq!(frame.calls.len(), 1);
+            assert_eq!(frame.calls[0].mesh, levels[level], "{} at {}", desc, distance);
+        }
+    }
+}
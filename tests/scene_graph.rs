@@ -0,0 +1,88 @@
+mod common;
+
+use std::rc::Rc;
+
+use kolo::scene_graph::{self, SceneNode};
+
+use common::assert_near;
+
+
+fn position(node: &SceneNode) -> [f32; 3]
+{
+    let world = scene_graph::world_matrix(node);
+
+    [ world[3][0], world[3][1], world[3][2] ]
+}
+
+
+
+#[test]
+fn children_follow_their_parent()
+{
+    let board = scene_graph::group([ 0., -6., -12. ]);
+    let peg = scene_graph::group([ 15., 0., 0. ]);
+    let disk = scene_graph::group([ 0., 1.2, 0. ]);
+
+    scene_graph::attach(&board, &peg);
+    scene_graph::attach(&peg, &disk);
+
+    assert_eq!(position(&disk), [ 15., -4.8, -12. ]);
+
+    // The cached world matrix of the disk is dropped when the board moves.
+    board.borrow_mut().set_position([ 0., 0., 0. ]);
+    assert_eq!(position(&disk), [ 15., 1.2, 0. ]);
+
+    peg.borrow_mut().set_scale([ 2., 2., 2. ]);
+    assert_eq!(position(&disk), [ 15., 2.4, 0. ]);
+}
+
+
+
+#[test]
+fn reparented_node_stays_in_place()
+{
+    let first = scene_graph::group([ -15., -6., -12. ]);
+    let second = scene_graph::group([ 0., -6., -10. ]);
+    let disk = scene_graph::group([ 0., 0.6, 0. ]);
+
+    scene_graph::attach(&first, &disk);
+    scene_graph::reparent(&second, &disk);
+
+    assert_near(position(&disk), [ -15., -5.4, -12. ], 1e-4);
+    assert_near(disk.borrow().get_pos(), [ -15., 0.6, -2. ], 1e-4);
+    assert!(scene_graph::children(&first).is_empty());
+    assert!(scene_graph::parent(&disk).is_some_and(|parent| Rc::ptr_eq(&parent, &second)));
+
+    scene_graph::detach(&disk);
+    assert_near(position(&disk), [ -15., 0.6, -2. ], 1e-4);
+}
+
+
+
+#[test]
+fn nodes_are_not_attached_into_a_loop()
+{
+    let board = scene_graph::group([ 0., -6., -12. ]);
+    let peg = scene_graph::group([ 15., 0., 0. ]);
+    let disk = scene_graph::group([ 0., 1.2, 0. ]);
+
+    assert!(scene_graph::attach(&board, &peg));
+    assert!(scene_graph::attach(&peg, &disk));
+
+    assert!(!scene_graph::attach(&disk, &disk));
+    assert!(!scene_graph::attach(&disk, &board));
+    assert!(!scene_graph::reparent(&peg, &board));
+
+    // The graph is as it was and the world matrices can still be worked out.
+    assert!(scene_graph::parent(&board).is_none());
+    assert!(scene_graph::parent(&disk).is_some_and(|parent| Rc::ptr_eq(&parent, &peg)));
+    assert_eq!(scene_graph::children(&disk).len(), 0);
+    assert_eq!(position(&disk), [ 15., -4.8, -12. ]);
+    assert_eq!(position(&board), [ 0., -6., -12. ]);
+
+    // Going down is fine.
+    assert!(scene_graph::reparent(&board, &disk));
+    assert!(scene_graph::is_within(&disk, &board));
+    assert!(!scene_graph::is_within(&disk, &peg));
+    assert_near(position(&disk), [ 15., -4.8, -12. ], 1e-4);
+}
@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use image::RgbaImage;
+
+use kolo::{
+    actor_trait::{Actor, ActorBase},
+    ball_actor::ABall,
+    base_mesh_trait::IntoDesc,
+    donut_actor::ADonut,
+    donut_mesh::DonutMeshFactory,
+    renderer::Renderer,
+    software_backend::SoftwareBackend,
+    sphere_mesh::SphereMeshFactory,
+    stick_actor::AStick,
+    stick_mesh::StickMeshFactory,
+};
+
+
+const GOLDEN_SCENE: &str = "tests/golden/scene.png";
+
+// Set to write the golden images again after an intended change of the rendering.
+const UPDATE_GOLDEN: &str = "KOLO_UPDATE_GOLDEN";
+
+const CAMERA: [[f32; 4]; 4] = [ [ 1., 0., 0., 0. ],
+                                [ 0., 1., 0., 0. ],
+                                [ 0., 0., 1., 0. ],
+                                [ 0., -1., -10., 1. ] ];
+const PROJECTION: [[f32; 4]; 4] = [ [ 1.3, 0., 0., 0. ],
+                                    [ 0., 1.73, 0., 0. ],
+                                    [ 0., 0., -1., -1. ],
+                                    [ 0., 0., -0.2, 0. ] ];
+
+
+// A donut on a stick next to a ball, every colour fixed.
+fn scene(renderer: &mut Renderer) -> Vec<Rc<RefCell<ActorBase>>>
+{
+    let donut = ADonut::initialize(DonutMeshFactory::new(2., 0.6).into_desc(), renderer).unwrap();
+    let stick = AStick::initialize(StickMeshFactory::sized(0.25, 8.).into_desc(), renderer).unwrap();
+    let ball = ABall::initialize(SphereMeshFactory::new(1.5).into_desc(), renderer).unwrap();
+
+    let mut bases = Vec::new();
+
+    for (actor, color, position, tilt) in [ (donut.actor_base, [ 0.8, 0.2, 0.1, 1. ], [ -2., 0., 0. ], 1.3),
+                                            (stick.actor_base, [ 0.2, 0.5, 0.8, 1. ], [ -2., 2., 0. ], 1.57),
+                                            (ball.actor_base, [ 0.3, 0.8, 0.2, 1. ], [ 3.5, 1., 1. ], 0.) ]
+    {
+        let mut base = actor.borrow_mut();
+
+        base.color = color;
+        base.rotate_x(tilt);
+        base.set_position(position);
+        base.update_world();
+
+        drop(base);
+        bases.push(actor);
+    }
+
+    bases
+}
+
+
+
+fn draw(bases: &[Rc<RefCell<ActorBase>>], renderer: &mut Renderer, camera: &[[f32; 4]; 4]) -> RgbaImage
+{
+    let mut backend = SoftwareBackend::new(160, 120);
+
+    renderer.begin(camera, &PROJECTION);
+    bases.iter().for_each(|base| renderer.push(&base.borrow()));
+    renderer.submit(&mut backend);
+
+    backend.image().clone()
+}
+
+
+
+// Float rounding may differ between machines, a few pixels on the edges may come out differently.
+fn assert_matches_golden(image: &RgbaImage, path: &str)
+{
+    if std::env::var_os(UPDATE_GOLDEN).is_some() {
+        image.save(path).unwrap();
+        return;
+    }
+
+    let golden = image::open(path).unwrap().to_rgba8();
+    assert_eq!(golden.dimensions(), image.dimensions());
+
+    let differing = golden.pixels()
+                          .zip(image.pixels())
+                          .filter(|(a, b)| a.0.iter().zip(b.0.iter()).any(|(a, b)| a.abs_diff(*b) > 2))
+                          .count();
+
+    assert!(differing <= 10, "{} pixels differ from {}, run with {} set to update it", differing, path, UPDATE_GOLDEN);
+}
+
+
+
+#[test]
+fn scene_matches_golden_image()
+{
+    let mut renderer = Renderer::new();
+    let bases = scene(&mut renderer);
+
+    assert_matches_golden(&draw(&bases, &mut renderer, &CAMERA), GOLDEN_SCENE);
+}
+
+
+
+#[test]
+fn actors_behind_the_camera_draw_nothing()
+{
+    let mut renderer = Renderer::new();
+    let bases = scene(&mut renderer);
+
+    // Turned around, the scene is behind the camera.
+    let mut camera = CAMERA;
+    camera[0][0] = -1.;
+    camera[2][2] = -1.;
+    camera[3][2] = 10.;
+
+    let image = draw(&bases, &mut renderer, &camera);
+
+    assert!(image.pixels().all(|pixel| pixel.0 == [ 0, 0, 0, 255 ]));
+}
@@ -0,0 +1,91 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+use kolo::{
+    ascii_view,
+    london_rules::LondonRules,
+    tower_state::TowerState,
+};
+
+
+#[test]
+fn towers_are_drawn_by_width()
+{
+    let mut state = TowerState::tower(2, 0);
+    state.move_disk(0, 2);
+
+    assert_eq!(ascii_view::draw_towers(&state),
+               concat!("   |      |      |\n",
+                       " ==|==    |     =|=\n",
+                       "---------------------\n",
+                       "   1      2      3\n"));
+}
+
+
+
+#[test]
+fn grouped_disks_show_their_group()
+{
+    let mut state = TowerState::empty();
+    state.pegs[1] = vec![ LondonRules::ball(0), LondonRules::ball(2) ];
+
+    assert_eq!(ascii_view::draw_towers(&state),
+               concat!("  |    |    |\n",
+                       "  |   =c=   |\n",
+                       "  |   =a=   |\n",
+                       "---------------\n",
+                       "  1    2    3\n"));
+}
+
+
+
+fn terminal(args: &[&str]) -> std::process::Child
+{
+    Command::new(env!("CARGO_BIN_EXE_kolo_terminal")).args(args)
+                                                      .stdin(Stdio::piped())
+                                                      .stdout(Stdio::piped())
+                                                      .stderr(Stdio::piped())
+                                                      .spawn()
+                                                      .unwrap()
+}
+
+
+
+#[test]
+fn auto_gamer_moves_are_drawn_one_by_one()
+{
+    let mut game = terminal(&[ "--disks", "3" ]);
+    game.stdin.take().unwrap().write_all(b"a\n").unwrap();
+
+    let output = game.wait_with_output().unwrap();
+    let text = String::from_utf8(output.stdout).unwrap();
+    let moves: Vec<&str> = text.lines().filter(|line| line.starts_with("moves: ")).collect();
+
+    assert!(output.status.success());
+    assert_eq!(moves.len(), 8, "{}", text);
+    assert!(moves.iter().enumerate().all(|(i, line)| line.starts_with(&format!("moves: {},", i))), "{}", text);
+}
+
+
+
+#[test]
+fn closed_output_ends_the_game_quietly()
+{
+    let mut game = terminal(&[ "--disks", "3" ]);
+    let mut stdout = BufReader::new(game.stdout.take().unwrap());
+    let mut line = String::new();
+
+    while !line.starts_with("moves: ") {
+        line.clear();
+        stdout.read_line(&mut line).unwrap();
+    }
+
+    // Nobody reads what the move prints anymore.
+    drop(stdout);
+    game.stdin.take().unwrap().write_all(b"13\na\n").unwrap();
+
+    let output = game.wait_with_output().unwrap();
+
+    assert_ne!(output.status.code(), Some(101));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use std::f32::consts::PI;
+
+use kolo::{
+    actor_trait::ActorBase,
+    math,
+    quaternion::Quaternion,
+    transform::Transform,
+};
+
+use common::{assert_matrix_near, assert_near};
+
+
+fn placed() -> Transform
+{
+    let rotation = Quaternion::from_axis_angle([ 1., 2., -0.5 ], 0.8);
+
+    Transform::new([ 3., -1., 7. ], rotation, [ 2., 2., 2. ])
+}
+
+
+
+#[test]
+fn actor_rotations_match_the_math_matrices()
+{
+    let mut base = ActorBase::group();
+    base.rotate_x(PI * 0.5);
+    base.rotate_x(0.3);
+    base.set_scale([ 2., 2., 2. ]);
+    base.set_position([ 1., 2., 3. ]);
+    base.update_world();
+
+    let mut scale = vecmath::mat4_id();
+    math::scale_translation(&mut scale, [ 2., 2., 2. ]);
+    let mut expected = vecmath::col_mat4_mul(math::rotation_x(PI * 0.5 + 0.3), scale);
+    math::pos_translation(&mut expected, [ 1., 2., 3. ]);
+
+    assert_matrix_near(base.get_model(), expected);
+
+    let mut base = ActorBase::group();
+    base.rotate_y(1.1);
+    base.update_world();
+    assert_matrix_near(base.get_model(), math::rotation_y(1.1));
+
+    let mut base = ActorBase::group();
+    base.rotate_z(-0.7);
+    base.update_world();
+    assert_matrix_near(base.get_model(), math::rotation_z(-0.7));
+}
+
+
+
+#[test]
+fn compose_and_inverse_match_the_matrices()
+{
+    let parent = placed();
+    let child = Transform::new([ 0., 1.2, -4. ], Quaternion::from_axis_angle([ 0., 1., 0. ], 2.5), [ 0.5, 0.5, 0.5 ]);
+
+    assert_matrix_near(parent.compose(&child).to_matrix(), vecmath::col_mat4_mul(parent.to_matrix(), child.to_matrix()));
+    assert_matrix_near(parent.inverse().to_matrix(), vecmath::mat4_inv(parent.to_matrix()));
+    assert_matrix_near(parent.compose(&parent.inverse()).to_matrix(), vecmath::mat4_id());
+
+    let point = [ -2., 5., 0.5 ];
+    let moved = vecmath::col_mat4_transform(parent.to_matrix(), [ point[0], point[1], point[2], 1. ]);
+    assert_near(parent.transform_point(point), [ moved[0], moved[1], moved[2] ], 1e-4);
+}
+
+
+
+#[test]
+fn quaternions_round_trip_through_matrices()
+{
+    let rotation = placed().rotation;
+
+    let back = Quaternion::from_matrix(&rotation.to_matrix());
+    assert!((rotation.dot(&back).abs() - 1.).abs() < 1e-5, "{:?} isn't {:?}", back, rotation);
+
+    // Turning half way round around each axis takes the other branches of the conversion.
+    for axis in [ [ 1., 0., 0. ], [ 0., 1., 0. ], [ 0., 0., 1. ] ]
+    {
+        let half_turn = Quaternion::from_axis_angle(axis, PI);
+        assert_matrix_near(Quaternion::from_matrix(&half_turn.to_matrix()).to_matrix(), half_turn.to_matrix());
+    }
+}
+
+
+
+#[test]
+fn look_at_points_minus_z_at_the_target()
+{
+    let eye = [ 4., 3., 10. ];
+    let target = [ -2., 0., 1. ];
+    let look = Transform::look_at(eye, target, [ 0., 1., 0. ]);
+
+    let forward = look.transform_vector([ 0., 0., -1. ]);
+    assert_near(forward, vecmath::vec3_normalized(vecmath::vec3_sub(target, eye)), 1e-4);
+
+    // No roll, the right hand side stays level.
+    assert!(look.transform_vector([ 1., 0., 0. ])[1].abs() < 1e-5);
+    assert_near(look.translation, eye, 1e-4);
+}
+
+
+
+#[test]
+fn slerp_turns_at_a_constant_speed()
+{
+    let start = Transform::identity();
+    let end = Transform::new([ 10., 0., -4. ], Quaternion::from_axis_angle([ 0., 1., 0. ], PI * 0.5), [ 3., 3., 3. ]);
+
+    assert_eq!(start.slerp(&end, 0.), start);
+    assert_matrix_near(start.slerp(&end, 1.).to_matrix(), end.to_matrix());
+
+    let half = start.slerp(&end, 0.5);
+    assert_near(half.translation, [ 5., 0., -2. ], 1e-4);
+    assert_near(half.scale, [ 2., 2., 2. ], 1e-4);
+
+    let quarter_turn = Quaternion::from_axis_angle([ 0., 1., 0. ], PI * 0.25);
+    assert!((half.rotation.dot(&quarter_turn) - 1.).abs() < 1e-5, "{:?}", half.rotation);
+
+    // The long way round is never taken.
+    let almost_full_turn = Quaternion::from_axis_angle([ 0., 1., 0. ], PI * 1.9);
+    let halfway = Quaternion::identity().slerp(&almost_full_turn, 0.5);
+    assert!(halfway.dot(&Quaternion::from_axis_angle([ 0., 1., 0. ], -PI * 0.05)).abs() > 0.9999);
+}